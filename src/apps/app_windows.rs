@@ -1,4 +1,6 @@
 use super::App;
+use crate::components::notifications::{self, Level};
+use crate::helpers::location::{page_location, query_param, url_encode};
 use crate::helpers::AppState;
 use egui::{Context, ScrollArea, Ui};
 use std::collections::BTreeSet;
@@ -21,6 +23,7 @@ impl Default for Apps {
             Box::<super::challenge_info::ChallengeInfoApp>::default(),
             Box::<super::code_editor::CodeEditor>::default(),
             Box::<super::binary_upload::BinaryUpload>::default(),
+            Box::<super::SubmissionsApp>::default(),
             Box::<super::PasswordResetApp>::default(),
         ])
     }
@@ -28,10 +31,16 @@ impl Default for Apps {
 
 impl Apps {
     pub fn from_apps(apps: Vec<Box<dyn App>>) -> Self {
+        Self {
+            apps,
+            open: Self::default_open(),
+        }
+    }
+
+    fn default_open() -> BTreeSet<String> {
         let mut open = BTreeSet::new();
         open.insert(super::login_app::LoginApp::default().name().to_owned());
-
-        Self { apps, open }
+        open
     }
 
     #[allow(dead_code)] //inhibit warnings when target =/= WASM
@@ -40,7 +49,16 @@ impl Apps {
         ui.label(format!("{} apps", apps.len()));
         for app in apps {
             let mut is_open = open.contains(app.name());
-            ui.toggle_value(&mut is_open, app.name());
+            ui.horizontal(|ui| {
+                if ui
+                    .small_button("⧉")
+                    .on_hover_text("Open in a new tab")
+                    .clicked()
+                {
+                    detach(ui.ctx(), app.as_ref());
+                }
+                ui.toggle_value(&mut is_open, app.name());
+            });
             set_open(open, app.name(), is_open);
         }
     }
@@ -68,6 +86,39 @@ fn set_open(open: &mut BTreeSet<String>, key: &'static str, is_open: bool) {
     }
 }
 
+/// Opens just `app` in a new browser tab, showing what it shows here.
+/// If the browser blocks the popup the link is copied instead.
+fn detach(ctx: &Context, app: &dyn App) {
+    let Some((url, _)) = page_location() else {
+        return;
+    };
+    let mut link = format!("{}?panel={}", url, url_encode(app.name()));
+    if let Some(query) = app.share_query() {
+        link.push('&');
+        link.push_str(&query);
+    }
+    if !open_in_new_tab(&link) {
+        ctx.output_mut(|o| o.copied_text = link);
+        notifications::notify(
+            ctx,
+            Level::Warning,
+            "Popup blocked, the link was copied instead",
+        );
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn open_in_new_tab(url: &str) -> bool {
+    web_sys::window()
+        .and_then(|w| w.open_with_url_and_target(url, "_blank").ok().flatten())
+        .is_some()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn open_in_new_tab(_url: &str) -> bool {
+    false
+}
+
 // ----------------------------------------------------------------------------
 
 #[derive(serde::Deserialize, serde::Serialize)]
@@ -89,6 +140,34 @@ impl Default for AppWindows {
 }
 
 impl AppWindows {
+    /// Names of the windows that are currently open
+    pub fn open_windows(&self) -> &BTreeSet<String> {
+        &self.apps.open
+    }
+
+    pub fn set_open_windows(&mut self, open: BTreeSet<String>) {
+        self.apps.open = open;
+    }
+
+    /// Shows only the app named by the `panel` query parameter, for tabs opened with
+    /// [`detach`]. Returns false, leaving the windows alone, if there isn't one.
+    pub fn open_detached(&mut self) -> bool {
+        let panel = page_location().and_then(|(_, query)| query_param(&query, "panel"));
+        let Some(app) = panel.and_then(|p| self.apps.apps.iter().find(|a| a.name() == p)) else {
+            return false;
+        };
+        self.apps.open = BTreeSet::from([app.name().to_owned()]);
+        true
+    }
+
+    /* Back to the windows we open on first start, and forget
+    where windows were moved to. Everything else egui keeps for us,
+    like the score cache and favourites, is left alone */
+    fn reset_layout(&mut self, ctx: &Context) {
+        self.apps.open = Apps::default_open();
+        ctx.memory_mut(|m| m.reset_areas());
+    }
+
     #[allow(dead_code)] //inhibit warnings when target =/= WASM
     pub fn set_app_state_ref(&mut self, app_state: Arc<Mutex<AppState>>) {
         self.app_state = app_state;
@@ -117,6 +196,7 @@ impl AppWindows {
 
                 ui.separator();
 
+                self.session_expired_ui(ui);
                 self.app_list_ui(ui);
 
                 ui.separator();
@@ -145,8 +225,29 @@ impl AppWindows {
         self.show_windows(ctx);
     }
 
+    fn session_expired_ui(&mut self, ui: &mut egui::Ui) {
+        if !self.app_state.lock().unwrap().session_expired {
+            return;
+        }
+        ui.colored_label(
+            ui.visuals().warn_fg_color,
+            "Session expired, please log in again",
+        );
+        if ui.button("🔑 Log in").clicked() {
+            let login = super::login_app::LoginApp::default().name();
+            set_open(&mut self.apps.open, login, true);
+            self.app_state.lock().unwrap().session_expired = false;
+        }
+        ui.separator();
+    }
+
     #[allow(dead_code)] //inhibit warnings when target =/= WASM
     fn show_windows(&mut self, ctx: &Context) {
+        // Another app wants some code opened in the editor
+        if self.app_state.lock().unwrap().reopen_submission.is_some() {
+            let editor = super::code_editor::CodeEditor::default().name();
+            set_open(&mut self.apps.open, editor, true);
+        }
         self.apps.windows(ctx);
     }
 
@@ -158,6 +259,14 @@ impl AppWindows {
 
                 self.apps.checkboxes(ui);
             });
+            ui.separator();
+            if ui
+                .button("Reset layout")
+                .on_hover_text("Close all windows and put them back where they started")
+                .clicked()
+            {
+                self.reset_layout(ui.ctx());
+            }
         });
     }
 }
@@ -1,10 +1,16 @@
 use std::borrow::BorrowMut;
 use std::sync::{Arc, Mutex};
 
+use crate::components::{
+    code_view,
+    confirm_submission::{self, Choice},
+    notifications::{self, Level},
+    output_panel,
+};
 use crate::helpers::{
     fetchers::Requestor,
-    submission::{Submission, SubmissionResult},
-    AppState, Languages,
+    submission::{self, FailureReason, SourceFile, Submission, SubmissionResult},
+    AppState,
 };
 use std::future::Future;
 use std::sync::mpsc::{channel, Receiver, Sender};
@@ -14,18 +20,61 @@ struct Binary {
     bytes: Vec<u8>,
 }
 
+impl Binary {
+    /// The web backend hands over the bytes, natively we only get a path.
+    fn from_dropped(file: &egui::DroppedFile) -> Option<Self> {
+        if let Some(bytes) = &file.bytes {
+            return Some(Self {
+                filename: file.name.clone(),
+                bytes: bytes.to_vec(),
+            });
+        }
+
+        let path = file.path.as_ref()?;
+        let bytes = std::fs::read(path)
+            .map_err(|e| log::error!("Failed to read {}: {}", path.display(), e))
+            .ok()?;
+        Some(Self {
+            filename: path.file_name()?.to_string_lossy().to_string(),
+            bytes,
+        })
+    }
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, serde::Deserialize, serde::Serialize)]
+enum UploadMode {
+    /// The file is source code and is sent as text
+    Code,
+    /// The file is an executable and is sent as form data
+    #[default]
+    Binary,
+}
+
 #[derive(serde::Deserialize, serde::Serialize)]
 #[serde(default)]
 pub struct BinaryUpload {
     #[serde(skip)]
     last_result: SubmissionResult,
-    url: String,
+    mode: UploadMode,
     #[serde(skip)]
     run: Submission,
     #[serde(skip)]
+    file: Option<Vec<u8>>,
+    #[serde(skip)]
     binary_channel: (Sender<Binary>, Receiver<Binary>),
+    /// Sent along with the main file in code mode
+    #[serde(skip)]
+    extra_files: Vec<SourceFile>,
+    #[serde(skip)]
+    extra_channel: (Sender<Binary>, Receiver<Binary>),
     #[serde(skip)]
     submitter: Option<Requestor>,
+    /// Whether the submission `last_result` belongs to was only a test run
+    #[serde(skip)]
+    submitted_test: bool,
+    /// A scored submission waiting on the user to confirm it
+    #[serde(skip)]
+    confirm_scored: bool,
     #[serde(skip)]
     app_state: Arc<Mutex<AppState>>,
 }
@@ -33,15 +82,19 @@ pub struct BinaryUpload {
 impl Default for BinaryUpload {
     fn default() -> Self {
         Self {
-            url: option_env!("BACKEND_URL")
-                .unwrap_or("http://123.4.5.6:3000/")
-                .to_string(),
             run: Submission {
                 filename: "Select Binary".to_string(),
+                test: true,
                 ..Default::default()
             },
+            mode: UploadMode::default(),
+            file: None,
             binary_channel: channel(),
+            extra_files: Vec::new(),
+            extra_channel: channel(),
             submitter: None,
+            submitted_test: true,
+            confirm_scored: false,
             last_result: SubmissionResult::NotStarted,
             app_state: Arc::new(Mutex::new(AppState::default())),
         }
@@ -49,12 +102,136 @@ impl Default for BinaryUpload {
 }
 
 impl BinaryUpload {
-    fn submit(&mut self) {
+    fn submit(&mut self, ctx: &egui::Context) {
+        submission::remember_language(ctx, &self.run);
         let submission = self.run.clone();
-        let url = format!("{}api/game/binary", self.url);
+        let endpoint = match self.mode {
+            UploadMode::Code => "game/submit",
+            UploadMode::Binary => "game/binary",
+        };
+        let url = self.app_state.lock().unwrap().api_url(endpoint);
         let app_state = Arc::clone(&self.app_state);
+        self.submitted_test = submission.test;
         self.submitter = submission.sender(app_state, &url);
     }
+
+    /// Scored submissions end up on the leaderboard, so they're confirmed first.
+    fn confirm_scored_ui(&mut self, ctx: &egui::Context) {
+        if !self.confirm_scored {
+            return;
+        }
+        match confirm_submission::confirm_submission(ctx, &self.run) {
+            Choice::Undecided => {}
+            Choice::Submit => {
+                self.confirm_scored = false;
+                self.submit(ctx);
+            }
+            Choice::Cancel => self.confirm_scored = false,
+        }
+    }
+
+    /// Puts the selected file into the submission as either code or a binary.
+    fn prepare(&mut self) -> Result<(), String> {
+        let bytes = self.file.clone().ok_or("No file selected")?;
+        match self.mode {
+            UploadMode::Code => {
+                let code = String::from_utf8(bytes).map_err(|_| "File is not valid UTF-8 text")?;
+                self.run.code = Some(code);
+                self.run.binary = None;
+                self.run.files = self.extra_files.clone();
+            }
+            UploadMode::Binary => {
+                self.run.code = None;
+                self.run.binary = Some(bytes);
+                self.run.files.clear();
+            }
+        }
+        self.run.validate()
+    }
+
+    fn result_ui(&self, ui: &mut egui::Ui) {
+        let visuals = ui.visuals();
+        let color = match self.last_result {
+            SubmissionResult::Success { .. } => egui::Color32::GREEN,
+            SubmissionResult::Failure { .. } => visuals.error_fg_color,
+            SubmissionResult::NotAuthorized => visuals.warn_fg_color,
+            _ => visuals.text_color(),
+        };
+        let kind = match self.submitted_test {
+            true => "Test run",
+            false => "Scored",
+        };
+        let text = match &self.last_result {
+            SubmissionResult::NotAuthorized => self.last_result.to_string(),
+            SubmissionResult::Failure { reason, .. } => {
+                format!("{} {}: {}", reason.icon(), kind, self.last_result)
+            }
+            result => format!("{}: {}", kind, result),
+        };
+        ui.horizontal_wrapped(|ui| ui.colored_label(color, text));
+        if let SubmissionResult::Failure { reason, .. } = &self.last_result {
+            ui.weak(reason.guidance());
+        }
+        if self.submitted_test {
+            ui.weak("Test runs aren't posted to the leaderboard");
+            if let Some(output) = self.last_result.output() {
+                output_panel::output_panel(ui, output);
+            }
+        }
+    }
+
+    /// Further source files for challenges that need more than one.
+    fn extra_files_ui(&mut self, ui: &mut egui::Ui) {
+        ui.label("Extra files:");
+        let mut remove = None;
+        for (i, SourceFile { filename, code }) in self.extra_files.iter().enumerate() {
+            ui.horizontal(|ui| {
+                if ui.small_button("🗑").on_hover_text("Remove").clicked() {
+                    remove = Some(i);
+                }
+                ui.label(filename)
+                    .on_hover_text(format!("{} lines", code.lines().count()));
+            });
+        }
+        if let Some(i) = remove {
+            self.extra_files.remove(i);
+        }
+        if ui.button("➕ Add files").clicked() {
+            self.app_state
+                .clone()
+                .lock()
+                .unwrap()
+                .update_activity_timer();
+            let sender = self.extra_channel.0.clone();
+            let task = rfd::AsyncFileDialog::new().pick_files();
+            execute(async move {
+                for file in task.await.unwrap_or_default() {
+                    let bytes = file.read().await;
+                    let _ = sender.send(Binary {
+                        filename: file.file_name(),
+                        bytes,
+                    });
+                }
+            });
+        }
+    }
+
+    fn progress_ui(&self, ui: &mut egui::Ui) {
+        let Some(submitter) = &self.submitter else {
+            return;
+        };
+        match submitter.upload_progress().and_then(|p| p.fraction()) {
+            Some(fraction) => {
+                ui.add(egui::ProgressBar::new(fraction).show_percentage());
+            }
+            None => {
+                ui.horizontal(|ui| {
+                    ui.add(egui::Spinner::new());
+                    ui.label("Uploading...");
+                });
+            }
+        }
+    }
 }
 
 impl super::App for BinaryUpload {
@@ -72,23 +249,50 @@ impl super::App for BinaryUpload {
             .open(open)
             .default_height(500.0)
             .show(ctx, |ui| self.ui(ui));
+        if *open {
+            self.confirm_scored_ui(ctx);
+            let dropped = ctx.input(|i| i.raw.dropped_files.clone());
+            if let Some(f) = dropped.iter().find_map(Binary::from_dropped) {
+                let _ = self.binary_channel.0.send(f);
+            }
+        }
 
         if let Ok(f) = self.binary_channel.1.try_recv() {
             self.run.filename = f.filename;
-            self.run.binary = Some(f.bytes);
+            self.file = Some(f.bytes);
+            ctx.request_repaint();
+        }
+        while let Ok(f) = self.extra_channel.1.try_recv() {
+            match String::from_utf8(f.bytes) {
+                Ok(code) => {
+                    // Picking a file again replaces it
+                    self.extra_files.retain(|file| file.filename != f.filename);
+                    self.extra_files.push(SourceFile {
+                        filename: f.filename,
+                        code,
+                    });
+                }
+                Err(_) => notifications::notify(
+                    ctx,
+                    Level::Error,
+                    format!("{} is not valid UTF-8 text", f.filename),
+                ),
+            }
             ctx.request_repaint();
         }
 
-        let submission = Submission::check_sender(&mut self.submitter);
+        let submission = Submission::check_sender(&mut self.submitter, self.submitted_test);
         match submission {
             SubmissionResult::NotStarted => {}
             _ => {
+                notifications::notify_submission(ctx, &submission);
                 self.last_result = submission;
                 ctx.request_repaint();
             }
         }
         if let Some(fetcher) = self.submitter.borrow_mut() {
-            if fetcher.refresh_context() {
+            // Upload progress arrives outside of egui, so keep redrawing to show it
+            if fetcher.refresh_context() || fetcher.upload_progress().is_some() {
                 ctx.request_repaint();
             }
         }
@@ -103,11 +307,13 @@ impl super::View for BinaryUpload {
                 ui.style_mut().wrap = Some(false);
                 ui.set_min_width(60.0);
 
-                for language in Languages::iter() {
+                let languages = self.app_state.lock().unwrap().languages.clone();
+                for language in languages {
                     ui.selectable_value(&mut self.run.language, language, format!("{}", language));
                 }
             });
 
+        let previous_challenge = self.run.challenge.clone();
         egui::ComboBox::from_label("Challenge")
             .selected_text(self.run.challenge.clone().unwrap_or("None".to_string()))
             .show_ui(ui, |ui| {
@@ -123,7 +329,23 @@ impl super::View for BinaryUpload {
                 }
             });
 
-        ui.checkbox(&mut self.run.test, "Test");
+        if self.run.challenge != previous_challenge {
+            if let Some(challenge) = &self.run.challenge {
+                self.run.language = submission::last_language(ui.ctx(), challenge);
+            }
+        }
+
+        ui.horizontal(|ui| {
+            ui.radio_value(&mut self.mode, UploadMode::Binary, "Binary");
+            ui.radio_value(&mut self.mode, UploadMode::Code, "Code");
+        });
+
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut self.run.test, true, "🧪 Run tests only")
+                .on_hover_text("Check your program against the tests, nothing is posted");
+            ui.selectable_value(&mut self.run.test, false, "🏆 Submit for score")
+                .on_hover_text("Score your program and post it to the leaderboard");
+        });
         ui.separator();
 
         if ui.button(self.run.filename.clone()).clicked() {
@@ -146,28 +368,79 @@ impl super::View for BinaryUpload {
             });
         }
 
+        if self.mode == UploadMode::Code {
+            if let Some(code) = self
+                .file
+                .as_deref()
+                .and_then(|f| std::str::from_utf8(f).ok())
+            {
+                ui.collapsing("Preview", |ui| {
+                    egui::ScrollArea::both().max_height(300.0).show(ui, |ui| {
+                        code_view::code_view(ui, code, Some(self.run.language));
+                    });
+                });
+            }
+            self.extra_files_ui(ui);
+        }
+
         if "Select Binary" != &self.run.filename {
             ui.separator();
-            if ui.button("Submit").clicked() {
+            let button = match self.run.test {
+                true => egui::Button::new("🧪 Run tests"),
+                false => egui::Button::new(
+                    egui::RichText::new("🏆 Submit for score")
+                        .color(ui.visuals().strong_text_color()),
+                )
+                .fill(ui.visuals().selection.bg_fill),
+            };
+            // Scores can still be looked at during maintenance, but nothing can be sent
+            let maintenance = self.app_state.lock().unwrap().maintenance.clone();
+            let enabled = self.submitter.is_none() && maintenance.is_none();
+            let mut response = ui.add_enabled(enabled, button);
+            if let Some(message) = maintenance {
+                response = response.on_disabled_hover_text(message);
+            }
+            if response.clicked() {
                 self.app_state
                     .clone()
                     .lock()
                     .unwrap()
                     .update_activity_timer();
-                match self.run.validate() {
-                    Ok(_) => {
-                        self.submit();
+                // Checked before asking, so the confirmation shows what will be sent
+                match self.prepare() {
+                    Ok(_) if confirm_submission::needs_confirmation(ui.ctx(), &self.run) => {
+                        self.confirm_scored = true;
                     }
+                    Ok(_) => self.submit(ui.ctx()),
                     Err(e) => {
-                        self.last_result = SubmissionResult::Failure { message: e };
+                        self.last_result = SubmissionResult::Failure {
+                            reason: FailureReason::Invalid,
+                            message: e,
+                            output: None,
+                        };
                     }
                 }
             }
+            self.progress_ui(ui);
         }
 
         if SubmissionResult::NotStarted != self.last_result {
             ui.separator();
-            ui.horizontal_wrapped(|ui| ui.label(format!("Result: {}", self.last_result)));
+            self.result_ui(ui);
+        }
+
+        if ui.ctx().input(|i| !i.raw.hovered_files.is_empty()) {
+            let rect = ui.min_rect();
+            let color = ui.visuals().selection.stroke.color;
+            ui.painter()
+                .rect_stroke(rect.expand(4.0), 4.0, egui::Stroke::new(2.0, color));
+            ui.painter().text(
+                rect.center(),
+                egui::Align2::CENTER_CENTER,
+                "Drop file here",
+                egui::TextStyle::Heading.resolve(ui.style()),
+                color,
+            );
         }
     }
 }
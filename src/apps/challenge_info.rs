@@ -1,5 +1,11 @@
-use crate::helpers::AppState;
+use crate::components::{challenge_badge, favorites, refresh_button};
+use crate::helpers::{
+    location,
+    markdown::{self, Block, Section},
+    AppState,
+};
 use egui_commonmark::*;
+use std::collections::HashSet;
 use std::sync::{Arc, Mutex};
 
 #[derive(PartialEq, Clone, Copy, serde::Deserialize, serde::Serialize)]
@@ -9,6 +15,16 @@ enum FilterOption {
     UniqueLanguage,
 }
 
+#[derive(Default, PartialEq, Clone)]
+enum InfoState {
+    #[default]
+    Loading,
+    /// The backend has no challenges at all
+    Empty,
+    Ready,
+    Failed(String),
+}
+
 #[derive(serde::Deserialize, serde::Serialize)]
 pub struct ChallengeInfoApp {
     selected_challenge: String,
@@ -16,6 +32,27 @@ pub struct ChallengeInfoApp {
     active_challenge: Option<String>,
     instructions: String,
     #[serde(skip)]
+    state: InfoState,
+    #[serde(skip)]
+    sections: Vec<(Section, Vec<Block>)>,
+    #[serde(skip)]
+    scroll_to_section: Option<usize>,
+    /// Images in the instructions being shown, to notice which fail to load
+    #[serde(skip)]
+    image_urls: Vec<String>,
+    #[serde(skip)]
+    failed_images: HashSet<String>,
+    #[serde(skip)]
+    markdown_cache: CommonMarkCache,
+    /// Searches the instructions of every challenge while it isn't empty
+    #[serde(skip)]
+    search: String,
+    /// Only lists challenges in this category
+    #[serde(default)]
+    category: Option<String>,
+    #[serde(skip)]
+    initialised: bool,
+    #[serde(skip)]
     app_state: Arc<Mutex<AppState>>,
 }
 
@@ -25,11 +62,275 @@ impl Default for ChallengeInfoApp {
             selected_challenge: "".to_string(),
             active_challenge: None,
             instructions: "None".to_string(),
+            state: InfoState::Loading,
+            sections: Vec::new(),
+            scroll_to_section: None,
+            image_urls: Vec::new(),
+            failed_images: HashSet::new(),
+            markdown_cache: CommonMarkCache::default(),
+            search: String::new(),
+            category: None,
+            initialised: false,
             app_state: Arc::new(Mutex::new(AppState::default())),
         }
     }
 }
 
+impl ChallengeInfoApp {
+    fn update_state(&mut self) {
+        let state = {
+            let app_state = self.app_state.lock().unwrap();
+            match &app_state.challenges_error {
+                Some(e) => InfoState::Failed(e.clone()),
+                None if !app_state.challenges_loaded => InfoState::Loading,
+                None if app_state.challenges.items.is_empty() => InfoState::Empty,
+                None => InfoState::Ready,
+            }
+        };
+
+        if state == InfoState::Ready && self.state != InfoState::Ready {
+            self.active_challenge = None;
+        }
+        self.state = state;
+    }
+
+    fn refresh(&mut self) {
+        let fetching = {
+            let mut app_state = self.app_state.lock().unwrap();
+            app_state.update_activity_timer();
+            app_state.challenges_fetching
+        };
+        if fetching {
+            return;
+        }
+        self.failed_images.clear();
+        self.active_challenge = None;
+    }
+
+    /// Only called when the selection changes, so it's read straight from the challenge list
+    /// rather than kept around, and always matches what the backend last sent.
+    fn load_instructions(&self) -> String {
+        let app_state = self.app_state.lock().unwrap();
+        match app_state
+            .challenges
+            .get_instructions(self.selected_challenge.clone())
+        {
+            // Images are served by the backend next to the instructions
+            Some(instructions) => markdown::rewrite_images(&instructions, |alt, target| {
+                format!(
+                    "![{}]({})",
+                    alt,
+                    markdown::resolve_url(&app_state.backend_url, target)
+                )
+            }),
+            None => "Unable to load instructions".to_string(),
+        }
+    }
+}
+
+impl ChallengeInfoApp {
+    /// Splits the instructions up for display, with a placeholder for images that failed.
+    fn build_sections(&mut self) {
+        let mut image_urls = Vec::new();
+        let failed = &self.failed_images;
+        let instructions = markdown::rewrite_images(&self.instructions, |alt, target| {
+            let url = target.split_whitespace().next().unwrap_or_default();
+            image_urls.push(url.to_string());
+            match failed.contains(url) {
+                true => format!("🖼 *Image failed to load: {}*", alt),
+                false => format!("![{}]({})", alt, target),
+            }
+        });
+        self.image_urls = image_urls;
+        self.sections = markdown::split_sections(&instructions)
+            .into_iter()
+            .map(|section| {
+                let blocks = markdown::split_code_blocks(&section.body);
+                (section, blocks)
+            })
+            .collect();
+    }
+
+    /// egui's loaders keep every image once it's loaded, or failed to, so this is cheap.
+    fn check_images(&mut self, ctx: &egui::Context) {
+        let mut newly_failed = false;
+        for url in &self.image_urls {
+            if self.failed_images.contains(url) {
+                continue;
+            }
+            match ctx.try_load_image(url, egui::SizeHint::default()) {
+                // Nothing to load it with yet isn't the image's fault, try again once there is
+                Ok(_) | Err(egui::load::LoadError::NoImageLoaders) => {}
+                Err(e) => {
+                    log::warn!("Failed to load image {}: {}", url, e);
+                    self.failed_images.insert(url.clone());
+                    newly_failed = true;
+                }
+            }
+        }
+        if newly_failed {
+            self.build_sections();
+        }
+    }
+
+    fn contents_ui(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("Contents")
+            .default_open(false)
+            .show(ui, |ui| {
+                for (i, (section, _)) in self.sections.iter().enumerate() {
+                    if section.level == 0 {
+                        continue;
+                    }
+                    ui.horizontal(|ui| {
+                        ui.add_space(10.0 * (section.level - 1) as f32);
+                        if ui.link(&section.title).clicked() {
+                            self.scroll_to_section = Some(i);
+                        }
+                    });
+                }
+            });
+    }
+
+    fn instructions_ui(&mut self, ui: &mut egui::Ui) {
+        let scroll_to = self.scroll_to_section.take();
+        for (i, (_, blocks)) in self.sections.iter().enumerate() {
+            if scroll_to == Some(i) {
+                ui.scroll_to_cursor(Some(egui::Align::TOP));
+            }
+            for (j, block) in blocks.iter().enumerate() {
+                let viewer = CommonMarkViewer::new(("viewer", i, j));
+                match block {
+                    Block::Text(text) => viewer.show(ui, &mut self.markdown_cache, text),
+                    Block::Code { markdown, code } => {
+                        let rect = ui
+                            .scope(|ui| viewer.show(ui, &mut self.markdown_cache, markdown))
+                            .response
+                            .rect;
+                        copy_button_ui(ui, rect, ("copy", i, j), code);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl ChallengeInfoApp {
+    /// The challenges to pick from, grouped by category when they have one.
+    fn selection_ui(&mut self, ui: &mut egui::Ui) {
+        let challenges = self.app_state.lock().unwrap().challenges.clone();
+        let categories = challenges.categories();
+        if !categories.is_empty() {
+            // A category that went away with a reload shouldn't hide everything
+            if let Some(category) = &self.category {
+                if !categories.contains(category) {
+                    self.category = None;
+                }
+            }
+            egui::ComboBox::from_id_source("challenge_category")
+                .selected_text(self.category.as_deref().unwrap_or("All categories"))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.category, None, "All categories");
+                    for category in categories {
+                        ui.selectable_value(&mut self.category, Some(category.clone()), category);
+                    }
+                });
+        }
+
+        let favorites = favorites::favorites(ui.ctx());
+        let mut commands = challenges.commands();
+        if let Some(category) = &self.category {
+            commands.retain(|c| {
+                challenges.get(c).and_then(|c| c.category()) == Some(category.as_str())
+            });
+        }
+        let groups = favorites::grouped(&challenges, commands, &favorites);
+        let only_group = groups.len() == 1;
+        for (group, commands) in groups {
+            favorites::group_heading(ui, &group, only_group);
+            for command in commands {
+                ui.horizontal(|ui| {
+                    favorites::star_button(ui, &command, &favorites);
+                    let challenge = challenges.get(&command);
+                    let response =
+                        ui.radio_value(&mut self.selected_challenge, command.clone(), &command);
+                    if let Some(challenge) = challenge {
+                        response.on_hover_text(&challenge.name);
+                        challenge_badge::difficulty_badge(ui, challenge.difficulty());
+                    }
+                });
+            }
+        }
+    }
+
+    fn search_ui(&mut self, ui: &mut egui::Ui) {
+        let hits = self
+            .app_state
+            .lock()
+            .unwrap()
+            .challenges
+            .search(&self.search);
+        if hits.is_empty() {
+            ui.label(format!("No challenges mention \"{}\"", self.search.trim()));
+            return;
+        }
+        for hit in hits {
+            ui.horizontal(|ui| {
+                if ui.link(format!("{} - {}", hit.command, hit.name)).clicked() {
+                    self.selected_challenge = hit.command.clone();
+                    self.search.clear();
+                }
+                ui.weak(format!(
+                    "{} match{}",
+                    hit.matches,
+                    if hit.matches == 1 { "" } else { "es" }
+                ));
+            });
+            if let Some((before, matched, after)) = &hit.snippet {
+                let color = ui.visuals().text_color();
+                let font = egui::TextStyle::Body.resolve(ui.style());
+                let plain = egui::TextFormat::simple(font.clone(), color);
+                let highlighted = egui::TextFormat {
+                    background: ui.visuals().selection.bg_fill,
+                    color: ui.visuals().strong_text_color(),
+                    ..egui::TextFormat::simple(font, color)
+                };
+                let mut job = egui::text::LayoutJob::default();
+                job.append(before, 0.0, plain.clone());
+                job.append(matched, 0.0, highlighted);
+                job.append(after, 0.0, plain);
+                ui.label(job);
+            }
+            ui.separator();
+        }
+    }
+}
+
+/// Draws a copy button over the top right corner of `rect` without affecting the layout.
+fn copy_button_ui(ui: &mut egui::Ui, rect: egui::Rect, id: impl std::hash::Hash, text: &str) {
+    let size = egui::vec2(20.0, 20.0);
+    let button = egui::Rect::from_min_size(rect.right_top() + egui::vec2(-size.x - 4.0, 4.0), size);
+    let response = ui
+        .interact(button, ui.id().with(id), egui::Sense::click())
+        .on_hover_text("Copy to clipboard");
+
+    let color = if response.hovered() {
+        ui.visuals().strong_text_color()
+    } else {
+        ui.visuals().weak_text_color()
+    };
+    ui.painter().text(
+        button.center(),
+        egui::Align2::CENTER_CENTER,
+        "🗐",
+        egui::TextStyle::Button.resolve(ui.style()),
+        color,
+    );
+
+    if response.clicked() {
+        ui.output_mut(|o| o.copied_text = text.to_string());
+    }
+}
+
 impl super::App for ChallengeInfoApp {
     fn name(&self) -> &'static str {
         "📖 Challenge Info"
@@ -39,7 +340,31 @@ impl super::App for ChallengeInfoApp {
         self.app_state = app_state;
     }
 
+    fn share_query(&self) -> Option<String> {
+        Some(format!(
+            "challenge={}",
+            location::url_encode(&self.selected_challenge)
+        ))
+    }
+
     fn show(&mut self, ctx: &egui::Context, open: &mut bool) {
+        if !self.initialised {
+            self.initialised = true;
+            if let Some(challenge) = self.app_state.lock().unwrap().default_challenge.clone() {
+                self.selected_challenge = challenge;
+            }
+            let linked = location::page_location()
+                .and_then(|(_, query)| location::query_param(&query, "challenge"));
+            if let Some(challenge) = linked.filter(|c| !c.is_empty()) {
+                self.selected_challenge = challenge;
+            }
+        }
+        self.update_state();
+
+        if *open && super::refresh_shortcut_pressed(ctx, self.name()) {
+            self.refresh();
+        }
+
         let challenges_differ = match self.active_challenge.clone() {
             None => true,
             Some(active) => active != self.selected_challenge,
@@ -47,13 +372,11 @@ impl super::App for ChallengeInfoApp {
 
         if challenges_differ {
             self.active_challenge = Some(self.selected_challenge.clone());
-            self.instructions = self
-                .app_state
-                .lock()
-                .unwrap()
-                .challenges
-                .get_instructions(self.selected_challenge.clone())
-                .unwrap_or("Unable to load instructions".to_string());
+            self.instructions = self.load_instructions();
+            self.build_sections();
+        }
+        if *open {
+            self.check_images(ctx);
         }
 
         egui::Window::new(self.name())
@@ -78,30 +401,60 @@ impl super::View for ChallengeInfoApp {
             .resizable(false)
             .show_inside(ui, |ui| {
                 ui.vertical(|ui| {
-                    for challenge in self.app_state.lock().unwrap().challenges.items.iter() {
-                        ui.radio_value(
-                            &mut self.selected_challenge,
-                            challenge.command.clone(),
-                            &challenge.command,
+                    let searching = !self.search.trim().is_empty();
+                    ui.horizontal(|ui| {
+                        let response = ui.add(
+                            egui::TextEdit::singleline(&mut self.search)
+                                .hint_text("🔍 Search all")
+                                .desired_width(100.0),
                         );
-                    }
+                        // Give a failed challenge list another go rather than finding nothing
+                        if response.changed() && !searching && self.state != InfoState::Ready {
+                            self.app_state.lock().unwrap().reload_challenges();
+                        }
+                        if searching && ui.small_button("✖").on_hover_text("Clear").clicked() {
+                            self.search.clear();
+                        }
+                    });
                     ui.separator();
-                    if ui.button("Refresh").clicked() {
-                        self.app_state
-                            .clone()
-                            .lock()
-                            .unwrap()
-                            .update_activity_timer();
-                        self.active_challenge = None;
+                    self.selection_ui(ui);
+                    ui.separator();
+                    let fetching = self.app_state.lock().unwrap().challenges_fetching;
+                    if refresh_button::refresh_button(ui, fetching, true)
+                        .on_hover_text("Shortcut: R")
+                        .on_disabled_hover_text("Loading challenges...")
+                        .clicked()
+                    {
+                        self.refresh();
                     }
+                    ui.separator();
+                    self.contents_ui(ui);
                 });
             });
         egui::CentralPanel::default().show_inside(ui, |ui| {
             egui::ScrollArea::both()
                 .auto_shrink([false, false])
-                .show(ui, |ui| {
-                    let mut cache = CommonMarkCache::default();
-                    CommonMarkViewer::new("viewer").show(ui, &mut cache, &self.instructions);
+                .show(ui, |ui| match self.state.clone() {
+                    InfoState::Loading => {
+                        ui.horizontal(|ui| {
+                            ui.add(egui::Spinner::new());
+                            ui.label("Loading challenges...");
+                        });
+                    }
+                    InfoState::Empty => {
+                        ui.label("There are no challenges yet");
+                    }
+                    InfoState::Failed(e) => {
+                        ui.colored_label(
+                            ui.visuals().error_fg_color,
+                            format!("Failed to load challenges: {}", e),
+                        );
+                        if ui.button("Retry").clicked() {
+                            self.app_state.lock().unwrap().reload_challenges();
+                        }
+                    }
+                    InfoState::Ready if !self.search.trim().is_empty() => self.search_ui(ui),
+                    InfoState::Ready => self.instructions_ui(ui),
                 });
         });
     }
@@ -1,16 +1,29 @@
+use crate::components::{
+    code_input,
+    confirm_submission::{self, Choice},
+    diff_view, notifications, output_panel,
+};
 use crate::helpers::{
-    fetchers::Requestor,
-    submission::{Submission, SubmissionResult},
+    fetchers::{RequestStatus, Requestor},
+    submission::{self, FailureReason, Submission, SubmissionResult},
     AppState, Languages,
 };
 use std::borrow::BorrowMut;
 use std::sync::{Arc, Mutex};
 
+/// The code last submitted for the challenge, to compare against.
+#[derive(Default)]
+enum PreviousCode {
+    #[default]
+    Loading,
+    Missing,
+    Code(String),
+    Failed(String),
+}
+
 #[derive(serde::Deserialize, serde::Serialize)]
 #[serde(default)]
 pub struct CodeEditor {
-    #[serde(skip)]
-    url: String,
     #[serde(skip)]
     run: Submission,
     #[serde(skip)]
@@ -19,6 +32,20 @@ pub struct CodeEditor {
     code: String,
     #[serde(skip)]
     submitter: Option<Requestor>,
+    /// Whether the submission `submitter` has out is only a test run
+    #[serde(skip)]
+    submitted_test: bool,
+    #[serde(skip)]
+    compare: bool,
+    #[serde(skip)]
+    previous: PreviousCode,
+    /// The challenge `previous` belongs to, `None` until it's been fetched
+    #[serde(skip)]
+    previous_challenge: Option<Option<String>>,
+    #[serde(skip)]
+    previous_fetcher: Option<Requestor>,
+    #[serde(skip)]
+    confirm_scored: bool,
     #[serde(skip)]
     app_state: Arc<Mutex<AppState>>,
 }
@@ -31,26 +58,98 @@ impl Default for CodeEditor {
         };
         run.language = Languages::Python;
         Self {
-            url: option_env!("BACKEND_URL")
-                .unwrap_or("http://123.4.5.6:3000/")
-                .to_string(),
             run,
             code: "#A very simple example\nprint(\"Hello world!\")".into(),
             last_result: SubmissionResult::NotStarted,
             submitter: None,
+            submitted_test: false,
+            compare: false,
+            previous: PreviousCode::Loading,
+            previous_challenge: None,
+            previous_fetcher: None,
+            confirm_scored: false,
             app_state: Arc::new(Mutex::new(AppState::default())),
         }
     }
 }
 
 impl CodeEditor {
-    fn submit(&mut self) {
+    fn submit(&mut self, ctx: &egui::Context) {
+        submission::remember_language(ctx, &self.run);
         let submission = self.run.clone();
-        let url = format!("{}api/game/submit", self.url);
+        let url = self.app_state.lock().unwrap().api_url("game/submit");
         let app_state = Arc::clone(&self.app_state);
+        self.submitted_test = submission.test;
         self.submitter = submission.sender(app_state, &url);
     }
 
+    fn fetch_previous(&mut self) {
+        self.previous_challenge = Some(self.run.challenge.clone());
+        self.previous_fetcher = None;
+        let Some(challenge) = self.run.challenge.clone() else {
+            self.previous = PreviousCode::Missing;
+            return;
+        };
+
+        self.previous = PreviousCode::Loading;
+        let url = self
+            .app_state
+            .lock()
+            .unwrap()
+            .api_url(&format!("game/submissions/{}", challenge));
+        let app_state = Arc::clone(&self.app_state);
+        let mut getter = Requestor::new_get(app_state, &url, true);
+        getter.send();
+        self.previous_fetcher = Some(getter);
+    }
+
+    fn check_previous_promise(&mut self) {
+        let Some(getter) = &mut self.previous_fetcher else {
+            return;
+        };
+        self.previous = match getter.check_promise() {
+            RequestStatus::Success(text) => match submission::parse_submissions(&text) {
+                Ok(submissions) => submissions
+                    .into_iter()
+                    .find_map(|s| s.submission.code)
+                    .map_or(PreviousCode::Missing, PreviousCode::Code),
+                Err(e) => PreviousCode::Failed(e),
+            },
+            RequestStatus::Failed(e) => PreviousCode::Failed(e),
+            _ => return,
+        };
+        self.previous_fetcher = None;
+    }
+
+    fn compare_ui(&mut self, ui: &mut egui::Ui) {
+        if self.previous_challenge.as_ref() != Some(&self.run.challenge) {
+            self.fetch_previous();
+        }
+        match &self.previous {
+            PreviousCode::Loading => {
+                ui.horizontal(|ui| {
+                    ui.add(egui::Spinner::new());
+                    ui.label("Fetching your last submission...");
+                });
+            }
+            PreviousCode::Missing => {
+                ui.label("No earlier submission with code to compare against");
+            }
+            PreviousCode::Failed(e) => {
+                ui.colored_label(
+                    ui.visuals().error_fg_color,
+                    format!("Couldn't fetch your last submission: {}", e),
+                );
+            }
+            PreviousCode::Code(previous) => {
+                egui::ScrollArea::vertical()
+                    .id_source("diff")
+                    .max_height(300.0)
+                    .show(ui, |ui| diff_view::diff_view(ui, previous, &self.code));
+            }
+        }
+    }
+
     fn as_test_submission(&mut self) {
         self.run.code = Some(self.code.clone());
         self.run.test = true;
@@ -73,15 +172,33 @@ impl super::App for CodeEditor {
 
     fn show(&mut self, ctx: &egui::Context, open: &mut bool) {
         use super::View as _;
+        let reopened = self.app_state.lock().unwrap().reopen_submission.take();
+        if let Some(submission) = reopened {
+            self.code = submission.code.clone().unwrap_or_default();
+            self.run = submission;
+            self.last_result = SubmissionResult::NotStarted;
+        }
+
         egui::Window::new(self.name())
             .open(open)
             .default_height(500.0)
             .show(ctx, |ui| self.ui(ui));
+        if *open && self.confirm_scored {
+            match confirm_submission::confirm_submission(ctx, &self.run) {
+                Choice::Undecided => {}
+                Choice::Submit => {
+                    self.confirm_scored = false;
+                    self.submit(ctx);
+                }
+                Choice::Cancel => self.confirm_scored = false,
+            }
+        }
 
-        let submission = Submission::check_sender(&mut self.submitter);
+        let submission = Submission::check_sender(&mut self.submitter, self.submitted_test);
         match submission {
             SubmissionResult::NotStarted => {}
             _ => {
+                notifications::notify_submission(ctx, &submission);
                 self.last_result = submission;
             }
         }
@@ -90,6 +207,13 @@ impl super::App for CodeEditor {
                 ctx.request_repaint();
             }
         }
+
+        self.check_previous_promise();
+        if let Some(fetcher) = self.previous_fetcher.borrow_mut() {
+            if fetcher.refresh_context() {
+                ctx.request_repaint();
+            }
+        }
     }
 }
 
@@ -108,10 +232,12 @@ impl super::View for CodeEditor {
         ui.horizontal(|ui| {
             ui.label("Language:");
 
-            for l in Languages::iter() {
+            let languages = self.app_state.lock().unwrap().languages.clone();
+            for l in languages {
                 ui.selectable_value(&mut self.run.language, l, format!("{}", l));
             }
         });
+        let previous_challenge = self.run.challenge.clone();
         egui::ComboBox::from_label("Challenge")
             .selected_text(self.run.challenge.clone().unwrap_or("None".to_string()))
             .show_ui(ui, |ui| {
@@ -127,40 +253,46 @@ impl super::View for CodeEditor {
                 }
             });
 
+        if self.run.challenge != previous_challenge {
+            if let Some(challenge) = &self.run.challenge {
+                self.run.language = submission::last_language(ui.ctx(), challenge);
+            }
+        }
+
         let mut theme = egui_extras::syntax_highlighting::CodeTheme::from_memory(ui.ctx());
         ui.collapsing("Theme", |ui| {
             ui.group(|ui| {
                 theme.ui(ui);
                 theme.clone().store_in_memory(ui.ctx());
             });
+            code_input::tab_width_ui(ui);
         });
 
-        let mut layouter = |ui: &egui::Ui, string: &str, wrap_width: f32| {
-            let mut layout_job = egui_extras::syntax_highlighting::highlight(
-                ui.ctx(),
-                &theme,
-                string,
-                &self.run.language.to_string(),
-            );
-            layout_job.wrap.max_width = wrap_width;
-            ui.fonts(|f| f.layout_job(layout_job))
-        };
-
-        egui::ScrollArea::vertical().show(ui, |ui| {
-            ui.add(
-                egui::TextEdit::multiline(&mut self.code)
-                    .font(egui::TextStyle::Monospace) // for cursor height
-                    .code_editor()
-                    .desired_rows(10)
-                    .lock_focus(true)
-                    .desired_width(f32::INFINITY)
-                    .layouter(&mut layouter),
-            );
+        egui::ScrollArea::both().id_source("code").show(ui, |ui| {
+            code_input::code_input(ui, "code", &mut self.code, self.run.language, &theme);
         });
         ui.separator();
+        if ui
+            .checkbox(&mut self.compare, "Compare to last submission")
+            .changed()
+            && self.compare
+        {
+            self.fetch_previous();
+        }
+        if self.compare {
+            self.compare_ui(ui);
+            ui.separator();
+        }
         ui.horizontal(|ui| {
             ui.vertical(|ui| {
-                if ui.button("Submit").clicked() {
+                // Scores can still be looked at during maintenance, but nothing can be sent
+                let maintenance = self.app_state.lock().unwrap().maintenance.clone();
+                let down = maintenance.clone().unwrap_or_default();
+                if ui
+                    .add_enabled(maintenance.is_none(), egui::Button::new("Submit"))
+                    .on_disabled_hover_text(&down)
+                    .clicked()
+                {
                     log::debug!("Submitting code");
                     self.app_state
                         .clone()
@@ -169,15 +301,26 @@ impl super::View for CodeEditor {
                         .update_activity_timer();
                     self.as_submission();
                     match self.run.validate() {
+                        Ok(_) if confirm_submission::needs_confirmation(ui.ctx(), &self.run) => {
+                            self.confirm_scored = true;
+                        }
                         Ok(_) => {
-                            self.submit();
+                            self.submit(ui.ctx());
                         }
                         Err(e) => {
-                            self.last_result = SubmissionResult::Failure { message: e };
+                            self.last_result = SubmissionResult::Failure {
+                                reason: FailureReason::Invalid,
+                                message: e,
+                                output: None,
+                            };
                         }
                     }
                 }
-                if ui.button("Test").clicked() {
+                if ui
+                    .add_enabled(maintenance.is_none(), egui::Button::new("Test"))
+                    .on_disabled_hover_text(&down)
+                    .clicked()
+                {
                     log::debug!("Testing code");
                     self.app_state
                         .clone()
@@ -187,16 +330,34 @@ impl super::View for CodeEditor {
                     self.as_test_submission();
                     match self.run.validate() {
                         Ok(_) => {
-                            self.submit();
+                            self.submit(ui.ctx());
                         }
                         Err(e) => {
-                            self.last_result = SubmissionResult::Failure { message: e };
+                            self.last_result = SubmissionResult::Failure {
+                                reason: FailureReason::Invalid,
+                                message: e,
+                                output: None,
+                            };
                         }
                     }
                 }
             });
             ui.separator();
-            ui.vertical(|ui| ui.label(self.last_result.to_string()));
+            ui.vertical(|ui| {
+                if let SubmissionResult::NotAuthorized = self.last_result {
+                    ui.colored_label(ui.visuals().warn_fg_color, self.last_result.to_string());
+                    ui.weak("Your code is kept, submit again once you've logged in");
+                } else {
+                    ui.label(self.last_result.to_string());
+                }
+                if let SubmissionResult::Failure { reason, .. } = &self.last_result {
+                    ui.weak(format!("{} {}", reason.icon(), reason.guidance()));
+                }
+            });
         });
+        if let (true, Some(output)) = (self.run.test, self.last_result.output()) {
+            ui.separator();
+            output_panel::output_panel(ui, output);
+        }
     }
 }
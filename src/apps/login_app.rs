@@ -1,6 +1,7 @@
+use crate::components::password;
 use crate::helpers::{
     fetchers::{RequestStatus, Requestor},
-    AppState, LoginState,
+    refresh, AppState, LoginState,
 };
 use egui_notify::Toasts;
 use email_address::*;
@@ -59,6 +60,8 @@ pub enum LoginResponse {
     Success {
         status: String,
         access_token: String,
+        #[serde(default)]
+        expires_in: Option<i64>,
     },
     Failure {
         status: String,
@@ -75,9 +78,6 @@ enum LoginAppState {
 #[serde(default)]
 pub struct LoginApp {
     username: String,
-    token: Option<String>,
-    #[serde(skip)]
-    url: String,
     #[serde(skip)]
     login: LoginSchema,
     #[serde(skip)]
@@ -89,7 +89,7 @@ pub struct LoginApp {
     #[serde(skip)]
     login_requestor: Option<Requestor>,
     #[serde(skip)]
-    logout_requestor: Option<Requestor>,
+    logout_promise: refresh::RefreshPromise,
     #[serde(skip)]
     register_requestor: Option<Requestor>,
     #[serde(skip)]
@@ -100,12 +100,7 @@ pub struct LoginApp {
 
 impl Default for LoginApp {
     fn default() -> Self {
-        let url = option_env!("BACKEND_URL")
-            .unwrap_or("http://12.34.56.78:3000/")
-            .to_string();
-
         Self {
-            url,
             login: LoginSchema {
                 #[cfg(debug_assertions)]
                 email: "admin@admin.com".to_string(),
@@ -116,14 +111,13 @@ impl Default for LoginApp {
                 #[cfg(not(debug_assertions))]
                 password: "".to_string(),
             },
-            token: None,
             username: "".to_string(),
             state: LoginAppState::Login,
             register: RegisterSchema::default(),
             toasts: Toasts::default(),
             app_state: Default::default(),
             login_requestor: None,
-            logout_requestor: None,
+            logout_promise: None,
             register_requestor: None,
             reset_pass_requestor: None,
         }
@@ -133,7 +127,7 @@ impl Default for LoginApp {
 impl LoginApp {
     fn submit_login(&mut self) {
         let submission = Some(serde_json::to_string(&self.login).unwrap());
-        let url = format!("{}api/auth/login", self.url);
+        let url = self.app_state.lock().unwrap().api_url("auth/login");
         let app_state = Arc::clone(&self.app_state);
         let mut req = Requestor::new_post(app_state, &url, true, submission);
         req.send();
@@ -141,16 +135,13 @@ impl LoginApp {
     }
 
     fn submit_logout(&mut self) {
-        let url = format!("{}api/auth/logout", self.url);
-        let app_state = Arc::clone(&self.app_state);
-        let mut req = Requestor::new_post(app_state, &url, true, None);
-        req.send();
-        self.logout_requestor = Some(req);
+        let app_state = self.app_state.lock().unwrap();
+        self.logout_promise = refresh::logout(&app_state.api_url("auth/logout"), app_state.auth());
     }
 
     fn submit_register(&mut self) {
         let submission = Some(serde_json::to_string(&self.register).unwrap());
-        let url = format!("{}api/auth/register", self.url);
+        let url = self.app_state.lock().unwrap().api_url("auth/register");
         let app_state = Arc::clone(&self.app_state);
         let mut req = Requestor::new_post(app_state, &url, false, submission);
         req.send();
@@ -158,7 +149,11 @@ impl LoginApp {
     }
 
     fn submit_forgot_password(&mut self) {
-        let url = format!("{}api/auth/forgotpassword", self.url);
+        let url = self
+            .app_state
+            .lock()
+            .unwrap()
+            .api_url("auth/forgotpassword");
         let submission = Some(serde_json::to_string(&self.login.to_forgot_password()).unwrap());
         let app_state = Arc::clone(&self.app_state);
         let mut req = Requestor::new_post(app_state, &url, false, submission);
@@ -190,12 +185,21 @@ impl LoginApp {
                     log::debug!("Success: {}", text);
                     let result: LoginResponse = serde_json::from_str(text).unwrap();
                     match result {
-                        LoginResponse::Success { .. } => {
+                        LoginResponse::Success {
+                            expires_in,
+                            access_token,
+                            ..
+                        } => {
                             self.toasts
                                 .info(format!("Logged in: {}", &self.login.email))
                                 .set_duration(Some(Duration::from_secs(5)));
 
                             AppState::set_logged_in(&self.app_state);
+                            AppState::set_token_lifetime(
+                                &self.app_state,
+                                expires_in.map(chrono::Duration::seconds),
+                            );
+                            AppState::set_access_token(&self.app_state, access_token);
                         }
                         LoginResponse::Failure { status: _, message } => {
                             log::error!("Failed to login: {}", message);
@@ -212,28 +216,30 @@ impl LoginApp {
     }
 
     fn check_logout_promise(&mut self) {
-        let getter = &mut self.logout_requestor;
-
-        if let Some(getter) = getter {
-            let result = &getter.check_promise();
-            match result {
-                RequestStatus::Failed(err) => {
-                    self.toasts
-                        .error(format!("Failed: {}", err))
-                        .set_duration(Some(Duration::from_secs(5)));
+        let result = match &self.logout_promise {
+            Some(promise) => match promise.ready() {
+                Some(result) => result.clone(),
+                None => return,
+            },
+            None => return,
+        };
+        self.logout_promise = None;
+
+        match result {
+            Ok(response) => {
+                log::debug!("Success: {:?}", response);
+                self.toasts
+                    .info(format!("Logged out: {}", &self.login.email))
+                    .set_duration(Some(Duration::from_secs(5)));
+                self.login.password.clear();
+                AppState::set_logged_out(&self.app_state);
+            }
+            Err(err) => {
+                self.toasts
+                    .error(format!("Failed: {}", err))
+                    .set_duration(Some(Duration::from_secs(5)));
 
-                    log::error!("Error sending: {}", err);
-                    self.logout_requestor = None;
-                }
-                RequestStatus::Success(text) => {
-                    log::debug!("Success: {}", text);
-                    self.toasts
-                        .info(format!("Logged out: {}", &self.login.email))
-                        .set_duration(Some(Duration::from_secs(5)));
-                    AppState::set_logged_out(&self.app_state);
-                    self.logout_requestor = None;
-                }
-                _ => {}
+                log::error!("Error sending: {}", err);
             }
         }
     }
@@ -308,7 +314,9 @@ impl LoginApp {
     fn ui_logged_in(&mut self, ui: &mut egui::Ui) {
         ui.horizontal(|ui| {
             ui.vertical(|ui| {
-                if ui.button("Logout").clicked() {
+                if self.logout_promise.is_some() {
+                    ui.add(egui::Spinner::new());
+                } else if ui.button("Logout").clicked() {
                     self.submit_logout()
                 }
             });
@@ -380,19 +388,10 @@ impl LoginApp {
                 ui.end_row();
 
                 ui.label("Password:");
-                ui.add(
-                    egui::widgets::text_edit::TextEdit::singleline(&mut self.register.password)
-                        .password(true),
-                );
-                ui.end_row();
-
-                ui.label("Confirm Password:");
-                ui.add(
-                    egui::widgets::text_edit::TextEdit::singleline(
-                        &mut self.register.confirm_password,
-                    )
-                    .password(true),
-                );
+                ui.add(password::password_confirm(
+                    &mut self.register.password,
+                    &mut self.register.confirm_password,
+                ));
                 ui.end_row();
             });
 
@@ -1,6 +1,6 @@
 pub mod app_windows;
 mod scoreboard_app;
-pub use scoreboard_app::ScoreBoardApp;
+pub use scoreboard_app::{scoreboard_widget, ScoreBoardApp, TableResponse, ViewOptions};
 mod challenge_info;
 pub use challenge_info::ChallengeInfoApp;
 pub mod login_app;
@@ -8,9 +8,31 @@ pub use login_app::LoginApp;
 pub mod binary_upload;
 pub mod code_editor;
 mod password_reset_app;
+mod submissions_app;
 use crate::helpers::AppState;
 pub use password_reset_app::PasswordResetApp;
 use std::sync::{Arc, Mutex};
+pub use submissions_app::SubmissionsApp;
+
+/// Whether a window should react to key presses: it's the top-most one
+/// and no text field is being typed in.
+pub fn window_has_keyboard(ctx: &egui::Context, window_name: &str) -> bool {
+    let layer = egui::LayerId::new(egui::Order::Middle, egui::Id::new(window_name));
+    let on_top = ctx.memory(|m| {
+        m.layer_ids()
+            .filter(|l| l.order == egui::Order::Middle)
+            .last()
+            == Some(layer)
+    });
+    let typing = ctx.memory(|m| m.focus().is_some());
+    on_top && !typing
+}
+
+/// `R` refreshes a window, as long as it has the keyboard.
+pub fn refresh_shortcut_pressed(ctx: &egui::Context, window_name: &str) -> bool {
+    window_has_keyboard(ctx, window_name)
+        && ctx.input(|i| i.modifiers.is_none() && i.key_pressed(egui::Key::R))
+}
 
 /// Something to view in the demo windows
 pub trait View {
@@ -26,4 +48,9 @@ pub trait App {
     fn show(&mut self, ctx: &egui::Context, open: &mut bool);
 
     fn set_app_state_ref(&mut self, app_state: Arc<Mutex<AppState>>);
+
+    /// Query parameters that get a detached copy of this app showing the same thing
+    fn share_query(&self) -> Option<String> {
+        None
+    }
 }
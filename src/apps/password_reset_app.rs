@@ -29,8 +29,6 @@ pub struct PasswordResetApp {
     #[serde(skip)]
     token: String,
     #[serde(skip)]
-    url: String,
-    #[serde(skip)]
     toasts: Toasts,
     #[serde(skip)]
     new_password: String,
@@ -44,12 +42,8 @@ pub struct PasswordResetApp {
 
 impl Default for PasswordResetApp {
     fn default() -> Self {
-        let url = option_env!("BACKEND_URL")
-            .unwrap_or("http://12.34.56.78:9999/")
-            .to_string();
         Self {
             requestor: Default::default(),
-            url,
             token: "".to_string(),
             email: "".to_string(),
             toasts: Toasts::default(),
@@ -67,7 +61,11 @@ impl PasswordResetApp {
         };
 
         let submission = Some(serde_json::to_string(&submission).unwrap());
-        let url = format!("{}api/auth/resetpassword/{}", self.url, self.token);
+        let url = self
+            .app_state
+            .lock()
+            .unwrap()
+            .api_url(&format!("auth/resetpassword/{}", self.token));
         let app_state = Arc::clone(&self.app_state);
         let mut req = Requestor::new_post(app_state, &url, true, submission);
         req.send();
@@ -1,43 +1,385 @@
+use crate::components::notifications::{self, Level};
+use crate::components::{challenge_badge, distribution, favorites, language_badge, refresh_button};
 use crate::helpers::{
-    fetchers::{RequestStatus, Requestor},
-    AppState,
+    download,
+    fetchers::RetryPolicy,
+    format::{time_ago, Formatting},
+    location::{page_location, url_decode, url_encode},
+    scores::{self, FetchError, FetchedScore},
+    visibility, AppState, Languages,
 };
+use poll_promise::Promise;
 use scoreboard_db::Builder as FilterBuilder;
 use scoreboard_db::Filter as ScoreBoardFilter;
 use scoreboard_db::{NiceTime, Score, ScoreBoard, SortColumn};
+use std::collections::HashMap;
+use std::str::FromStr;
 use std::sync::{Arc, Mutex};
-use std::{borrow::BorrowMut, str::FromStr};
 
-#[derive(PartialEq, Clone, Copy, serde::Deserialize, serde::Serialize)]
-enum FilterOption {
+const PAGE_SIZES: [usize; 4] = [25, 50, 100, 250];
+const CACHE_TTL_SECONDS: i64 = 30;
+/// How often to quietly try the backend again while showing cached scores
+const OFFLINE_RETRY_SECONDS: i64 = 30;
+/// Quiet period after the last settings change before scores are reloaded.
+const RELOAD_DEBOUNCE_MS: i64 = 300;
+
+#[derive(Debug, PartialEq, Clone, Copy, serde::Deserialize, serde::Serialize)]
+pub enum FilterOption {
     All,
     UniquePlayers,
     UniqueLanguage,
 }
 
-#[derive(Clone, serde::Deserialize, serde::Serialize)]
-enum FetchResponse {
-    Success(Vec<Score>),
-    Failure(String),
-    FailAuth,
+impl FilterOption {
+    fn as_param(&self) -> &'static str {
+        match self {
+            FilterOption::All => "all",
+            FilterOption::UniquePlayers => "unique_players",
+            FilterOption::UniqueLanguage => "unique_languages",
+        }
+    }
+
+    fn from_param(param: &str) -> Option<Self> {
+        match param {
+            "all" => Some(FilterOption::All),
+            "unique_players" => Some(FilterOption::UniquePlayers),
+            "unique_languages" => Some(FilterOption::UniqueLanguage),
+            _ => None,
+        }
+    }
+}
+
+/// The parts of a scoreboard view that can be shared as a link.
+/// Anything missing or invalid in the link is left as `None`.
+#[derive(Debug, Default, PartialEq)]
+struct ShareParams {
+    challenge: Option<String>,
+    filter: Option<FilterOption>,
+    sort_column: Option<String>,
+}
+
+fn share_query(challenge: &str, filter: FilterOption, sort_column: &str) -> String {
+    format!(
+        "challenge={}&filter={}&sort={}",
+        url_encode(challenge),
+        filter.as_param(),
+        url_encode(sort_column)
+    )
+}
+
+fn parse_share_query(query: &str) -> ShareParams {
+    let mut params = ShareParams::default();
+    for pair in query.trim_start_matches('?').split('&') {
+        let Some((key, value)) = pair.split_once('=') else {
+            continue;
+        };
+        let Some(value) = url_decode(value) else {
+            continue;
+        };
+        match key {
+            "challenge" if !value.is_empty() => params.challenge = Some(value),
+            "filter" => params.filter = FilterOption::from_param(&value),
+            "sort" if SortColumn::from_str(&value).is_ok() => params.sort_column = Some(value),
+            _ => {}
+        }
+    }
+    params
+}
+
+#[derive(Default, PartialEq, Clone, Copy, serde::Deserialize, serde::Serialize)]
+enum Density {
+    #[default]
+    Comfortable,
+    Compact,
+}
+
+impl Density {
+    fn row_height(&self, text_height: f32) -> f32 {
+        match self {
+            Density::Comfortable => text_height + 6.0,
+            Density::Compact => text_height,
+        }
+    }
+
+    fn item_spacing(&self) -> egui::Vec2 {
+        match self {
+            Density::Comfortable => egui::vec2(8.0, 3.0),
+            Density::Compact => egui::vec2(4.0, 0.0),
+        }
+    }
+}
+
+#[derive(PartialEq, Clone, Copy, serde::Deserialize, serde::Serialize)]
+enum AutoRefresh {
+    Off,
+    Seconds5,
+    Seconds15,
+    Seconds60,
+}
+
+impl AutoRefresh {
+    fn iter() -> impl Iterator<Item = Self> {
+        use AutoRefresh::*;
+        [Off, Seconds5, Seconds15, Seconds60].iter().copied()
+    }
+
+    fn period(&self) -> Option<chrono::Duration> {
+        match self {
+            AutoRefresh::Off => None,
+            AutoRefresh::Seconds5 => Some(chrono::Duration::seconds(5)),
+            AutoRefresh::Seconds15 => Some(chrono::Duration::seconds(15)),
+            AutoRefresh::Seconds60 => Some(chrono::Duration::seconds(60)),
+        }
+    }
+}
+
+impl std::fmt::Display for AutoRefresh {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.period() {
+            None => write!(f, "Off"),
+            Some(period) => write!(f, "{}s", period.num_seconds()),
+        }
+    }
+}
+
+/// How far back submissions are shown, for competitions where only recent entries count.
+#[derive(PartialEq, Clone, Copy, Debug, Default)]
+enum DateRange {
+    #[default]
+    AllTime,
+    Last24Hours,
+    ThisWeek,
+    Last30Days,
+}
+
+impl DateRange {
+    fn iter() -> impl Iterator<Item = Self> {
+        use DateRange::*;
+        [AllTime, Last24Hours, ThisWeek, Last30Days].iter().copied()
+    }
+
+    /// The earliest submission time that's still shown, weeks start on Monday.
+    fn since(&self, now: chrono::DateTime<chrono::Utc>) -> Option<chrono::DateTime<chrono::Utc>> {
+        use chrono::Datelike;
+        match self {
+            DateRange::AllTime => None,
+            DateRange::Last24Hours => Some(now - chrono::Duration::hours(24)),
+            DateRange::ThisWeek => {
+                let days = now.weekday().num_days_from_monday();
+                let monday = now.date_naive() - chrono::Duration::days(days.into());
+                Some(monday.and_hms_opt(0, 0, 0)?.and_utc())
+            }
+            DateRange::Last30Days => Some(now - chrono::Duration::days(30)),
+        }
+    }
+}
+
+impl std::fmt::Display for DateRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DateRange::AllTime => write!(f, "All time"),
+            DateRange::Last24Hours => write!(f, "Last 24h"),
+            DateRange::ThisWeek => write!(f, "This week"),
+            DateRange::Last30Days => write!(f, "Last 30 days"),
+        }
+    }
+}
+
+pub type ScoreKey = (String, String, u64);
+
+pub fn score_key(name: &str, command: &str, time_ns: f64) -> ScoreKey {
+    (name.to_string(), command.to_string(), time_ns.to_bits())
+}
+
+/// Marks which challenge a score came from, for the merged "All Challenges" board.
+/// Backends never send a score's `hash`, so the challenge is kept there, where it
+/// stays with the score through filtering and sorting.
+fn tag_challenge(score: &mut Score, challenge: &str) {
+    score.hash = challenge.to_string();
+}
+
+fn challenge_of(score: &Score) -> &str {
+    &score.hash
+}
+
+/// Submission times keyed by score, skipping any that are missing or unreadable.
+fn submission_times(fetched: &[FetchedScore]) -> HashMap<ScoreKey, chrono::DateTime<chrono::Utc>> {
+    fetched
+        .iter()
+        .filter_map(|f| {
+            let at = chrono::DateTime::parse_from_rfc3339(f.submitted_at.as_ref()?).ok()?;
+            let s = &f.score;
+            Some((score_key(&s.name, &s.command, s.time_ns), at.into()))
+        })
+        .collect()
+}
+
+type ScorePromise = Promise<Result<Vec<FetchedScore>, FetchError>>;
+
+/// Scores per challenge along with when they were fetched
+type ScoreCache = HashMap<String, (chrono::DateTime<chrono::Utc>, Vec<Score>)>;
+
+/// Column widths the user dragged the table to, kept next to the cache in egui's
+/// persisted memory. The last column always takes what's left, so it isn't stored.
+#[derive(Clone, Debug, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+struct ColumnLayout {
+    /// Bumped by "Reset columns", giving the table a fresh id and so fresh state
+    generation: u32,
+    single: Vec<f32>,
+    /// With the extra Challenge column
+    all_challenges: Vec<f32>,
+}
+
+impl ColumnLayout {
+    fn widths(&self, all_challenges: bool) -> &Vec<f32> {
+        match all_challenges {
+            true => &self.all_challenges,
+            false => &self.single,
+        }
+    }
+
+    /// Returns true if anything changed.
+    fn set_widths(&mut self, all_challenges: bool, widths: Vec<f32>) -> bool {
+        let stored = match all_challenges {
+            true => &mut self.all_challenges,
+            false => &mut self.single,
+        };
+        let unchanged = stored.len() == widths.len()
+            && stored.iter().zip(&widths).all(|(a, b)| (a - b).abs() < 0.5);
+        if !unchanged {
+            *stored = widths;
+        }
+        !unchanged
+    }
+
+    fn reset(&mut self) {
+        *self = Self {
+            generation: self.generation.wrapping_add(1),
+            ..Default::default()
+        };
+    }
+}
+
+/// The table's columns, starting from the stored widths where there are any.
+fn table_columns(all_challenges: bool, widths: &[f32]) -> Vec<egui_extras::Column> {
+    use egui_extras::Column;
+
+    let width = |i: usize, default: f32| widths.get(i).copied().unwrap_or(default);
+    let auto = |i: usize| match widths.get(i) {
+        Some(width) => Column::initial(*width),
+        None => Column::auto(),
+    };
+    let mut columns = vec![
+        auto(0),
+        auto(1),
+        Column::initial(width(2, 100.0)).range(40.0..=300.0),
+        Column::initial(width(3, 100.0)).at_least(40.0).clip(true),
+    ];
+    if all_challenges {
+        columns.push(Column::initial(width(4, 80.0)).at_least(40.0).clip(true));
+    }
+    columns.push(Column::remainder());
+    columns
+}
+
+/// Pseudo challenge that merges the scores of every challenge into one table
+const ALL_CHALLENGES: &str = "All Challenges";
+
+/// What has come back so far for one press of refresh, which may cover several challenges.
+#[derive(Default)]
+struct PendingFetch {
+    challenge: String,
+    scores: Vec<Score>,
+    submitted: HashMap<ScoreKey, chrono::DateTime<chrono::Utc>>,
+    succeeded: usize,
+    failures: Vec<(String, FetchError)>,
 }
 
 #[derive(serde::Deserialize, serde::Serialize)]
 pub struct ScoreBoardApp {
     selected_challenge: String,
     filter: FilterOption,
+    language: Option<Languages>,
     sort_column: String,
+    sort_descending: bool,
 
+    /// Not saved, so the first frame after a restart always tries to load fresh scores
+    #[serde(skip)]
     active_challenge: Option<String>,
     active_filter: FilterOption,
+    active_language: Option<Languages>,
     active_sort_column: String,
+    active_sort_descending: bool,
 
     scores: Option<Vec<Score>>,
+    #[serde(deserialize_with = "page_size_from_saved")]
+    page_size: usize,
+    auto_refresh: AutoRefresh,
+    player_name: String,
+    density: Density,
+    show_distribution: bool,
+    /// Not saved, so every session starts out showing all time
+    #[serde(skip)]
+    date_range: DateRange,
+    #[serde(skip)]
+    scroll_to_row: Option<usize>,
+    #[serde(skip)]
+    last_fetch: chrono::DateTime<chrono::Utc>,
+    #[serde(skip)]
+    search: String,
+    #[serde(skip)]
+    page: usize,
+    #[serde(skip)]
+    rows: Vec<Score>,
+    /// The windows themselves aren't saved, so this lives in egui's persisted memory
+    /// to still be around when the app is started without a connection
+    #[serde(skip)]
+    cache: ScoreCache,
+    #[serde(skip)]
+    cache_dirty: bool,
+    #[serde(skip)]
+    columns: ColumnLayout,
+    #[serde(skip)]
+    columns_dirty: bool,
+    /// When the cached scores being shown instead of live ones were fetched
+    #[serde(skip)]
+    offline_since: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(skip)]
+    pending: PendingFetch,
     #[serde(skip)]
-    url: String,
+    fetch_error: Option<String>,
+    #[serde(skip)]
+    notified_error: Option<String>,
+    #[serde(skip)]
+    initialised: bool,
+    #[serde(skip)]
+    dirty_since: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(skip)]
+    details: Option<Score>,
+    /// Row picked with the keyboard or a click, as an index into `rows`
+    #[serde(skip)]
+    selected_row: Option<usize>,
+    /// Submission times from the last fetch of the selected challenge
+    #[serde(skip)]
+    submitted: HashMap<ScoreKey, chrono::DateTime<chrono::Utc>>,
+    /// Whether the scores on show have times to filter by, see [`has_submission_times`]
+    #[serde(skip)]
+    dated: bool,
+    /// `AppState::submissions_accepted` as of the last fetch it triggered
+    #[serde(skip)]
+    seen_submissions: u64,
+    /// The backend answered 429, nothing is fetched until then
+    #[serde(skip)]
+    rate_limited_until: Option<chrono::DateTime<chrono::Utc>>,
+    /// Challenge named by a share link, waiting for the challenge list to check it against
+    #[serde(skip)]
+    linked_challenge: Option<String>,
+    /// "All Challenges" was picked before there were any challenges to fetch for
+    #[serde(skip)]
+    awaiting_challenges: bool,
 
     #[serde(skip)]
-    score_fetcher: Option<Requestor>,
+    score_fetchers: Vec<(String, ScorePromise)>,
     #[serde(skip)]
     app_state: Arc<Mutex<AppState>>,
 }
@@ -47,77 +389,396 @@ impl Default for ScoreBoardApp {
         Self {
             selected_challenge: "".to_string(),
             filter: FilterOption::All,
+            language: None,
             sort_column: "time".to_string(),
-            url: option_env!("BACKEND_URL")
-                .unwrap_or("http://123.4.5.6:3000/")
-                .to_string(),
+            sort_descending: false,
 
             active_challenge: None,
             active_filter: FilterOption::All,
+            active_language: None,
             active_sort_column: "time".to_string(),
+            active_sort_descending: false,
             scores: None,
-            score_fetcher: None,
+            page_size: PAGE_SIZES[1],
+            auto_refresh: AutoRefresh::Off,
+            player_name: String::new(),
+            density: Density::Comfortable,
+            show_distribution: true,
+            date_range: DateRange::AllTime,
+            scroll_to_row: None,
+            last_fetch: chrono::Utc::now(),
+            search: String::new(),
+            page: 0,
+            rows: Vec::new(),
+            cache: HashMap::new(),
+            cache_dirty: false,
+            columns: ColumnLayout::default(),
+            columns_dirty: false,
+            offline_since: None,
+            pending: PendingFetch::default(),
+            fetch_error: None,
+            notified_error: None,
+            initialised: false,
+            dirty_since: None,
+            details: None,
+            selected_row: None,
+            submitted: HashMap::new(),
+            dated: false,
+            seen_submissions: 0,
+            rate_limited_until: None,
+            linked_challenge: None,
+            awaiting_challenges: false,
+            score_fetchers: Vec::new(),
             app_state: Arc::new(Mutex::new(AppState::default())),
         }
     }
 }
 
 impl ScoreBoardApp {
-    fn fetch(&mut self) {
-        self.scores = None;
-
-        let url = format!(
-            "{}api/game/scores/{}",
-            self.url,
-            self.app_state
-                .lock()
-                .unwrap()
-                .challenges
-                .get_table(self.selected_challenge.clone())
-        );
+    /// Starts fetching the selected challenge's scores, if it isn't already. Returns
+    /// whether a new fetch was started.
+    fn fetch(&mut self) -> bool {
+        if self.rate_limit_remaining().is_some() {
+            log::debug!("Rate limited, not fetching scores");
+            return false;
+        }
+        if self.fetching() && self.pending.challenge == self.selected_challenge {
+            log::debug!("Already fetching these scores");
+            return false;
+        }
+        // Dropping the old fetcher abandons its request, so a slow response
+        // for the previous challenge can't land after this one
+        self.score_fetchers.clear();
+        self.awaiting_challenges = false;
+        // Keep showing the cached scores while quietly checking if we're back online
+        if self.offline_since.is_none() {
+            self.scores = None;
+            self.rows.clear();
+        }
+        self.fetch_error = None;
+        self.last_fetch = chrono::Utc::now();
+        self.pending = PendingFetch {
+            challenge: self.selected_challenge.clone(),
+            ..Default::default()
+        };
+
+        let tables: Vec<(String, String)> = {
+            let app_state = self.app_state.lock().unwrap();
+            let challenges = &app_state.challenges;
+            if self.selected_challenge == ALL_CHALLENGES {
+                challenges
+                    .items
+                    .iter()
+                    .map(|c| (c.command.clone(), challenges.get_table(c.command.clone())))
+                    .collect()
+            } else {
+                let challenge = self.selected_challenge.clone();
+                vec![(challenge.clone(), challenges.get_table(challenge))]
+            }
+        };
+
+        if tables.is_empty() {
+            // Nothing would ever answer, so don't wait for it
+            log::debug!("No challenges to fetch scores for");
+            self.awaiting_challenges = true;
+            self.scores = Some(Vec::new());
+            self.update_rows();
+            return false;
+        }
 
         log::debug!("Fetching scoreboard info");
-        let app_state = Arc::clone(&self.app_state);
-        let mut getter = Requestor::new_get(app_state, &url, true);
-        getter.send();
-        self.score_fetcher = Some(getter);
+        let retry = RetryPolicy {
+            max_retries: 3,
+            base_delay_ms: 250,
+        };
+        for (challenge, table) in tables {
+            let (url, auth) = {
+                let app_state = self.app_state.lock().unwrap();
+                (
+                    app_state.api_url(&format!("game/scores/{}", table)),
+                    app_state.auth(),
+                )
+            };
+            let promise =
+                Promise::spawn_local(async move { scores::fetch_scores(&url, &auth, retry).await });
+            self.score_fetchers.push((challenge, promise));
+        }
+        true
+    }
+
+    fn formatting(&self) -> Formatting {
+        self.app_state.lock().unwrap().formatting
+    }
+
+    fn fetching(&self) -> bool {
+        !self.score_fetchers.is_empty()
+    }
+
+    /// Whole seconds left of a rate limit cooldown, rounded up.
+    fn rate_limit_remaining(&self) -> Option<i64> {
+        let remaining = self.rate_limited_until? - chrono::Utc::now();
+        let millis = remaining.num_milliseconds();
+        (millis > 0).then_some((millis + 999) / 1000)
+    }
+
+    fn refresh(&mut self) {
+        self.app_state
+            .clone()
+            .lock()
+            .unwrap()
+            .update_activity_timer();
+        // Explicit refreshes skip the debounce
+        self.dirty_since = None;
+        self.fetch();
     }
 
-    fn check_for_reload(&mut self) -> bool {
+    /// Waits for the settings to stop changing for a moment, so a burst of changes
+    /// only loads the scores once.
+    fn check_for_reload(&mut self, ctx: &egui::Context) -> bool {
+        let now = chrono::Utc::now();
+        if self.settings_changed() {
+            self.dirty_since = Some(now);
+        }
+        let Some(dirty_since) = self.dirty_since else {
+            return false;
+        };
+
+        let remaining = chrono::Duration::milliseconds(RELOAD_DEBOUNCE_MS) - (now - dirty_since);
+        if remaining > chrono::Duration::zero() {
+            if let Ok(remaining) = remaining.to_std() {
+                ctx.request_repaint_after(remaining);
+            }
+            return false;
+        }
+        self.dirty_since = None;
+
+        if let Some(scores) = self.cached_scores(&self.selected_challenge) {
+            log::debug!("Serving scores from cache");
+            self.score_fetchers.clear();
+            self.fetch_error = None;
+            self.scores = Some(scores);
+            self.update_rows();
+            return false;
+        }
+        true
+    }
+
+    fn settings_changed(&mut self) -> bool {
         let challenges_differ = match self.active_challenge.clone() {
             None => true,
             Some(active) => active != self.selected_challenge,
         };
         if challenges_differ
             || self.active_filter != self.filter
+            || self.active_language != self.language
             || self.active_sort_column != self.sort_column
+            || self.active_sort_descending != self.sort_descending
         {
             self.active_challenge = Some(self.selected_challenge.clone());
             self.active_filter = self.filter;
+            self.active_language = self.language;
             self.active_sort_column = self.sort_column.clone();
+            self.active_sort_descending = self.sort_descending;
             return true;
         }
         false
     }
 
-    fn check_fetch_promise(&mut self) -> RequestStatus {
-        let getter = &mut self.score_fetcher;
+    /// Caches scores fetched for `challenge`, but only shows them if that's still
+    /// the selected challenge.
+    fn apply_scores(&mut self, challenge: &str, scores: Vec<Score>) {
+        self.cache
+            .insert(challenge.to_string(), (chrono::Utc::now(), scores.clone()));
+        self.cache_dirty = true;
+        if challenge != self.selected_challenge {
+            log::debug!("Ignoring late scores for {}", challenge);
+            return;
+        }
+        self.offline_since = None;
+        self.scores = Some(scores);
+        self.update_rows();
+    }
+
+    /// Falls back to whatever was last fetched for the selected challenge, however old.
+    /// Returns false if there's nothing to fall back on.
+    fn show_offline_scores(&mut self) -> bool {
+        let Some((fetched_at, scores)) = self.cache.get(&self.selected_challenge).cloned() else {
+            return false;
+        };
+        log::info!("Backend unreachable, showing scores from {}", fetched_at);
+        self.offline_since = Some(fetched_at);
+        self.scores = Some(scores);
+        self.update_rows();
+        true
+    }
+
+    fn cached_scores(&self, challenge: &str) -> Option<Vec<Score>> {
+        let (fetched_at, scores) = self.cache.get(challenge)?;
+        let age = chrono::Utc::now() - *fetched_at;
+        (age < chrono::Duration::seconds(CACHE_TTL_SECONDS)).then(|| scores.clone())
+    }
+
+    /// Runs the filter pipeline once so the table only has to slice the result.
+    fn update_rows(&mut self) {
+        // Backends that don't send submission times can't be filtered by them
+        self.dated = self.scores.as_ref().map_or(false, |scores| {
+            has_submission_times(scores, &self.submitted)
+        });
+        let since = match self.dated {
+            true => self.date_range.since(chrono::Utc::now()),
+            false => None,
+        };
+        self.rows = match &self.scores {
+            Some(scores) => search_scores(
+                self.view_options()
+                    .apply(&submitted_since(scores, &self.submitted, since)),
+                &self.search,
+            ),
+            None => Vec::new(),
+        };
+        self.page = self
+            .page
+            .min(page_count(self.rows.len(), self.page_size) - 1);
+        self.selected_row = self.selected_row.filter(|&i| i < self.rows.len());
+    }
+
+    fn is_player(&self, score: &Score) -> bool {
+        is_player(&self.player_name, score)
+    }
+
+    fn view_options(&self) -> ViewOptions<'_> {
+        ViewOptions {
+            language: self.language,
+            filter: self.filter,
+            sort_column: self.sort_column.clone(),
+            sort_descending: self.sort_descending,
+            ..Default::default()
+        }
+    }
 
-        if let Some(getter) = getter {
-            let result = &getter.check_promise();
+    fn select_row(&mut self, index: usize) {
+        self.selected_row = Some(index);
+        self.page = index / self.page_size;
+        self.scroll_to_row = Some(index % self.page_size);
+    }
 
-            match result {
-                RequestStatus::Success(_) => {
-                    self.score_fetcher = None;
+    fn keyboard_navigation(&mut self, ctx: &egui::Context) {
+        use egui::Key;
+        let key = ctx.input(|i| {
+            [
+                Key::ArrowUp,
+                Key::ArrowDown,
+                Key::Home,
+                Key::End,
+                Key::Enter,
+            ]
+            .into_iter()
+            .find(|k| i.modifiers.is_none() && i.key_pressed(*k))
+        });
+        match key {
+            Some(Key::Enter) => {
+                if let Some(score) = self.selected_row.and_then(|i| self.rows.get(i)) {
+                    self.details = Some(score.clone());
                 }
-                RequestStatus::Failed(_) => {
-                    self.score_fetcher = None;
+            }
+            Some(key) => {
+                if let Some(index) = move_selection(self.selected_row, key, self.rows.len()) {
+                    self.select_row(index);
+                }
+            }
+            None => {}
+        }
+    }
+
+    fn jump_to_player(&mut self) {
+        if let Some(index) = self.rows.iter().position(|s| self.is_player(s)) {
+            self.page = index / self.page_size;
+            self.scroll_to_row = Some(index % self.page_size);
+        }
+    }
+
+    /// Collects the responses that have come in. Returns true while some are outstanding.
+    fn check_fetch_promises(&mut self, ctx: &egui::Context) -> bool {
+        let mut results = Vec::new();
+        let mut retry_after = None;
+        self.score_fetchers.retain(|(challenge, promise)| {
+            let Some(result) = promise.ready() else {
+                return true;
+            };
+            if let Err(FetchError::RateLimited(seconds)) = result {
+                retry_after = retry_after.max(Some(*seconds));
+            }
+            results.push((challenge.clone(), result.clone()));
+            false
+        });
+
+        if let Some(seconds) = retry_after {
+            let until = chrono::Utc::now() + chrono::Duration::seconds(seconds.into());
+            self.rate_limited_until = self.rate_limited_until.max(Some(until));
+        }
+        let finished = !results.is_empty() && self.score_fetchers.is_empty();
+        for (challenge, result) in results {
+            self.record_result(&challenge, result);
+        }
+        if finished {
+            self.finish_fetch(ctx);
+        }
+        !self.score_fetchers.is_empty()
+    }
+
+    fn record_result(&mut self, challenge: &str, result: Result<Vec<FetchedScore>, FetchError>) {
+        match result {
+            Ok(fetched) => {
+                self.pending.submitted.extend(submission_times(&fetched));
+                self.pending.scores.extend(fetched.into_iter().map(|f| {
+                    let mut score = f.score;
+                    tag_challenge(&mut score, challenge);
+                    score
+                }));
+                self.pending.succeeded += 1;
+            }
+            Err(e) => {
+                log::error!("{}: {}", challenge, e);
+                if e == FetchError::Unauthorized {
+                    AppState::set_session_expired(&self.app_state);
                 }
-                _ => {}
+                self.pending.failures.push((challenge.to_string(), e));
+            }
+        }
+    }
+
+    /// Shows the merged scores once every challenge has answered. If only some of them
+    /// failed, what did arrive is still shown along with a warning.
+    fn finish_fetch(&mut self, ctx: &egui::Context) {
+        let pending = std::mem::take(&mut self.pending);
+        if pending.succeeded == 0 {
+            // The cache only stands in for a backend that can't be reached. A backend that
+            // answers with an error should be heard, or a bad login looks like stale scores.
+            let offline = pending
+                .failures
+                .iter()
+                .all(|(_, e)| matches!(e, FetchError::Network(_)));
+            if offline && pending.challenge == self.selected_challenge && self.show_offline_scores()
+            {
+                return;
             }
-            return result.clone();
+            if let Some((_, e)) = pending.failures.into_iter().next() {
+                self.set_fetch_error(ctx, Level::Error, format!("Failed to fetch scores: {}", e));
+            }
+            return;
+        }
+
+        if pending.failures.is_empty() {
+            self.notified_error = None;
+        } else {
+            let challenges: Vec<&str> = pending.failures.iter().map(|(c, _)| c.as_str()).collect();
+            let warning = format!("Couldn't load scores for {}", challenges.join(", "));
+            self.set_fetch_error(ctx, Level::Warning, warning);
+        }
+        if pending.challenge == self.selected_challenge {
+            self.submitted = pending.submitted;
         }
-        RequestStatus::NotStarted
+        self.apply_scores(&pending.challenge, pending.scores);
     }
 }
 
@@ -130,15 +791,99 @@ impl super::App for ScoreBoardApp {
         self.app_state = app_state;
     }
 
+    fn share_query(&self) -> Option<String> {
+        Some(share_query(
+            &self.selected_challenge,
+            self.filter,
+            &self.sort_column,
+        ))
+    }
+
     fn show(&mut self, ctx: &egui::Context, open: &mut bool) {
-        if self.check_for_reload() {
+        let cache_id = egui::Id::new("scoreboard_cache");
+        let columns_id = egui::Id::new("scoreboard_columns");
+        if !self.initialised {
+            self.initialised = true;
+            if let Some(challenge) = self.app_state.lock().unwrap().default_challenge.clone() {
+                self.selected_challenge = challenge;
+            }
+            if let Some(cache) = ctx.data_mut(|d| d.get_persisted::<ScoreCache>(cache_id)) {
+                self.cache = cache;
+            }
+            if let Some(columns) = ctx.data_mut(|d| d.get_persisted::<ColumnLayout>(columns_id)) {
+                self.columns = columns;
+            }
+            if let Some((_, query)) = page_location() {
+                self.apply_share_params(parse_share_query(&query));
+            }
+        }
+        self.resolve_linked_challenge(ctx);
+        if self.awaiting_challenges && !self.app_state.lock().unwrap().challenges.items.is_empty() {
             self.fetch();
         }
 
-        if let Some(fetcher) = self.score_fetcher.borrow_mut() {
-            if fetcher.refresh_context() {
-                log::debug!("Refreshing context");
+        if self.check_for_reload(ctx) {
+            self.fetch();
+        }
+        // A closed window picks the fetch up again when it's reopened
+        if let (true, Some(until)) = (*open, self.rate_limited_until) {
+            match self.rate_limit_remaining() {
+                Some(_) => {
+                    let next_second = (until - chrono::Utc::now()).num_milliseconds() % 1000;
+                    ctx.request_repaint_after(std::time::Duration::from_millis(
+                        next_second.max(1) as u64
+                    ));
+                }
+                None => {
+                    self.rate_limited_until = None;
+                    self.fetch();
+                }
+            }
+        }
+        // Someone just submitted, so the scores are out of date. Scores already on
+        // their way may have left before the submission, so wait for them and go again.
+        let accepted = self.app_state.lock().unwrap().submissions_accepted;
+        if *open && accepted != self.seen_submissions {
+            if self.fetching() {
+                // Come back for it on the frame after they land
                 ctx.request_repaint();
+            } else if self.fetch() {
+                self.seen_submissions = accepted;
+            }
+        }
+
+        if self.cache_dirty {
+            self.cache_dirty = false;
+            let cache = self.cache.clone();
+            ctx.data_mut(|d| d.insert_persisted(cache_id, cache));
+        }
+        if self.columns_dirty {
+            self.columns_dirty = false;
+            let columns = self.columns.clone();
+            ctx.data_mut(|d| d.insert_persisted(columns_id, columns));
+        }
+
+        if *open && super::refresh_shortcut_pressed(ctx, self.name()) {
+            self.refresh();
+        }
+        if *open && super::window_has_keyboard(ctx, self.name()) {
+            self.keyboard_navigation(ctx);
+        }
+
+        let period = match self.offline_since {
+            Some(_) => Some(chrono::Duration::seconds(OFFLINE_RETRY_SECONDS)),
+            None => self.auto_refresh.period(),
+        };
+        // Paused while the tab is hidden, an overdue refresh goes out as soon as it's back
+        let hidden = visibility::page_hidden();
+        if let (true, false, Some(period)) = (*open, hidden, period) {
+            let remaining = period - (chrono::Utc::now() - self.last_fetch);
+            if remaining <= chrono::Duration::zero() {
+                if self.score_fetchers.is_empty() {
+                    self.fetch();
+                }
+            } else if let Ok(remaining) = remaining.to_std() {
+                ctx.request_repaint_after(remaining);
             }
         }
 
@@ -155,6 +900,10 @@ impl super::App for ScoreBoardApp {
                 use super::View as _;
                 self.ui(ui);
             });
+
+        if *open {
+            self.details_ui(ctx);
+        }
     }
 }
 
@@ -170,13 +919,31 @@ impl super::View for ScoreBoardApp {
                             ui.style_mut().wrap = Some(false);
                             ui.set_min_width(60.0);
 
-                            for challenge in self.app_state.lock().unwrap().challenges.items.iter()
-                            {
-                                ui.selectable_value(
-                                    &mut self.selected_challenge,
-                                    challenge.command.clone(),
-                                    &challenge.command,
-                                );
+                            ui.selectable_value(
+                                &mut self.selected_challenge,
+                                ALL_CHALLENGES.to_string(),
+                                ALL_CHALLENGES,
+                            );
+                            let favorites = favorites::favorites(ui.ctx());
+                            let challenges = self.app_state.lock().unwrap().challenges.clone();
+                            let groups =
+                                favorites::grouped(&challenges, challenges.commands(), &favorites);
+                            let only_group = groups.len() == 1;
+                            for (group, commands) in groups {
+                                favorites::group_heading(ui, &group, only_group);
+                                for command in commands {
+                                    ui.horizontal(|ui| {
+                                        favorites::star_button(ui, &command, &favorites);
+                                        ui.selectable_value(
+                                            &mut self.selected_challenge,
+                                            command.clone(),
+                                            &command,
+                                        );
+                                        let difficulty =
+                                            challenges.get(&command).and_then(|c| c.difficulty());
+                                        challenge_badge::difficulty_badge(ui, difficulty);
+                                    });
+                                }
                             }
                         });
 
@@ -191,19 +958,172 @@ impl super::View for ScoreBoardApp {
                     ui.radio_value(
                         &mut self.filter,
                         FilterOption::UniqueLanguage,
-                        "Unique Langauges",
+                        "Unique Languages",
                     );
                     ui.separator();
-                    if ui.button("Refresh").clicked() {
-                        self.app_state
-                            .clone()
-                            .lock()
-                            .unwrap()
-                            .update_activity_timer();
-                        self.fetch();
+                    ui.label("Density:");
+                    ui.horizontal(|ui| {
+                        ui.radio_value(&mut self.density, Density::Comfortable, "Comfortable");
+                        ui.radio_value(&mut self.density, Density::Compact, "Compact");
+                    });
+                    if ui
+                        .button("Reset columns")
+                        .on_hover_text("Put the column widths back to how they started")
+                        .clicked()
+                    {
+                        self.columns.reset();
+                        self.columns_dirty = true;
+                    }
+                    ui.checkbox(&mut self.show_distribution, "Show distribution")
+                        .on_hover_text("How the times shown are spread out");
+                    ui.separator();
+                    egui::ComboBox::from_label("Language")
+                        .selected_text(match self.language {
+                            Some(language) => language.to_string(),
+                            None => "All".to_string(),
+                        })
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut self.language, None, "All");
+                            let languages = self.app_state.lock().unwrap().languages.clone();
+                            for language in languages {
+                                ui.selectable_value(
+                                    &mut self.language,
+                                    Some(language),
+                                    language.to_string(),
+                                );
+                            }
+                        });
+                    if self.dated {
+                        let before = self.date_range;
+                        egui::ComboBox::from_label("Submitted")
+                            .selected_text(self.date_range.to_string())
+                            .show_ui(ui, |ui| {
+                                for range in DateRange::iter() {
+                                    ui.selectable_value(
+                                        &mut self.date_range,
+                                        range,
+                                        range.to_string(),
+                                    );
+                                }
+                            });
+                        if before != self.date_range {
+                            self.update_rows();
+                        }
+                    }
+                    ui.separator();
+                    ui.label("Search:");
+                    if ui
+                        .add(egui::TextEdit::singleline(&mut self.search).hint_text("Name"))
+                        .changed()
+                    {
+                        self.update_rows();
+                    }
+                    ui.separator();
+                    ui.label("Me:");
+                    ui.add(egui::TextEdit::singleline(&mut self.player_name).hint_text("Name"))
+                        .on_hover_text("Your rows will be highlighted");
+                    let found = self.rows.iter().any(|s| self.is_player(s));
+                    if ui
+                        .add_enabled(found, egui::Button::new("Jump to me"))
+                        .clicked()
+                    {
+                        self.jump_to_player();
+                    }
+                    ui.separator();
+                    self.pagination_ui(ui);
+                    ui.separator();
+                    if ui
+                        .add_enabled(!self.rows.is_empty(), egui::Button::new("Download CSV"))
+                        .clicked()
+                    {
+                        let filename = format!("scores_{}.csv", self.selected_challenge);
+                        if let Err(e) =
+                            download::download(&filename, &to_csv(&self.rows), "text/csv")
+                        {
+                            log::error!("Failed to download scores: {}", e);
+                        }
+                    }
+                    if ui
+                        .add_enabled(!self.rows.is_empty(), egui::Button::new("Download JSON"))
+                        .on_hover_text("The scores shown, with a description of each field")
+                        .clicked()
+                    {
+                        let filename = format!("scores_{}.json", self.selected_challenge);
+                        let json = to_json(&self.selected_challenge, &self.rows);
+                        if let Err(e) = download::download(&filename, &json, "application/json") {
+                            log::error!("Failed to download scores: {}", e);
+                        }
+                    }
+                    if ui
+                        .add_enabled(!self.rows.is_empty(), egui::Button::new("Copy as Markdown"))
+                        .on_hover_text("The page shown, as a table to paste into chat or docs")
+                        .clicked()
+                    {
+                        ui.output_mut(|o| o.copied_text = self.page_markdown());
+                        notifications::notify(ui.ctx(), Level::Info, "Table copied");
+                    }
+                    if ui
+                        .button("Copy share link")
+                        .on_hover_text("Link to this challenge with the current filter and sort")
+                        .clicked()
+                    {
+                        if let Some(link) = self.share_link() {
+                            ui.output_mut(|o| o.copied_text = link);
+                            notifications::notify(ui.ctx(), Level::Info, "Link copied");
+                        }
+                    }
+                    ui.separator();
+                    egui::ComboBox::from_label("Auto refresh")
+                        .selected_text(self.auto_refresh.to_string())
+                        .show_ui(ui, |ui| {
+                            for option in AutoRefresh::iter() {
+                                ui.selectable_value(
+                                    &mut self.auto_refresh,
+                                    option,
+                                    option.to_string(),
+                                );
+                            }
+                        });
+                    let refresh = refresh_button::refresh_button(
+                        ui,
+                        self.fetching(),
+                        self.rate_limited_until.is_none(),
+                    );
+                    let disabled_hint = match self.fetching() {
+                        true => "Loading scores...",
+                        false => "Rate limited by the server, please wait",
+                    };
+                    if refresh
+                        .on_hover_text("Shortcut: R")
+                        .on_disabled_hover_text(disabled_hint)
+                        .clicked()
+                    {
+                        self.refresh();
                     }
                 });
             });
+        if let Some(scores) = &self.scores {
+            egui::TopBottomPanel::bottom("scoreboard_footer").show_inside(ui, |ui| {
+                let formatting = self.formatting();
+                ui.label(row_count_text(self.rows.len(), scores.len(), &formatting));
+            });
+        }
+        // Times for different challenges can't be compared
+        if self.show_distribution
+            && !self.rows.is_empty()
+            && self.selected_challenge != ALL_CHALLENGES
+        {
+            egui::TopBottomPanel::top("scoreboard_distribution").show_inside(ui, |ui| {
+                let times: Vec<f64> = self.rows.iter().map(|s| s.time_ns).collect();
+                let mine = self
+                    .rows
+                    .iter()
+                    .filter(|s| self.is_player(s))
+                    .map(|s| s.time_ns)
+                    .reduce(f64::min);
+                distribution::distribution_ui(ui, &times, mine);
+            });
+        }
         egui::CentralPanel::default().show_inside(ui, |ui| {
             egui::ScrollArea::both()
                 .auto_shrink([false, false])
@@ -215,99 +1135,1377 @@ impl super::View for ScoreBoardApp {
 }
 
 impl ScoreBoardApp {
-    fn table_ui(&mut self, ui: &mut egui::Ui) {
-        use egui_extras::{Column, TableBuilder};
+    fn pagination_ui(&mut self, ui: &mut egui::Ui) {
+        egui::ComboBox::from_label("Page size")
+            .selected_text(self.page_size.to_string())
+            .show_ui(ui, |ui| {
+                for size in PAGE_SIZES {
+                    ui.selectable_value(&mut self.page_size, size, size.to_string());
+                }
+            });
+        self.page_size = self.page_size.max(1);
 
-        match self.check_fetch_promise() {
-            RequestStatus::Success(text) => {
-                self.score_fetcher = None;
-                self.scores = Some(serde_json::from_str(&text).unwrap());
+        let pages = page_count(self.rows.len(), self.page_size);
+        self.page = self.page.min(pages - 1);
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(self.page > 0, egui::Button::new("◀"))
+                .clicked()
+            {
+                self.page -= 1;
             }
-            RequestStatus::Failed(e) => {
-                self.score_fetcher = None;
-                let message = format!("Failed to fetch scores: {}", e);
-                log::error!("{}", message);
-                ui.label(message);
-            }
-            RequestStatus::InProgress => {
-                ui.label("Fetching scores...");
+            let formatting = self.formatting();
+            ui.label(format!(
+                "Page {} of {}",
+                formatting.count(self.page + 1),
+                formatting.count(pages)
+            ));
+            if ui
+                .add_enabled(self.page + 1 < pages, egui::Button::new("▶"))
+                .clicked()
+            {
+                self.page += 1;
             }
-            RequestStatus::NotStarted => {}
-        }
+        });
+    }
 
-        let text_height = egui::TextStyle::Body.resolve(ui.style()).size;
-        let table = TableBuilder::new(ui)
-            .striped(true)
+    fn apply_share_params(&mut self, params: ShareParams) {
+        self.linked_challenge = params.challenge;
+        if let Some(filter) = params.filter {
+            self.filter = filter;
+        }
+        if let Some(sort_column) = params.sort_column {
+            self.sort_column = sort_column;
+            self.sort_descending = false;
+        }
+    }
+
+    /// Switches to the challenge from a share link once the challenge list is in,
+    /// so a stale or mistyped link can't leave the board stuck on a missing table.
+    fn resolve_linked_challenge(&mut self, ctx: &egui::Context) {
+        if self.linked_challenge.is_none() {
+            return;
+        }
+        let known = {
+            let app_state = self.app_state.lock().unwrap();
+            if !app_state.challenges_loaded {
+                return;
+            }
+            let challenge = self.linked_challenge.as_deref().unwrap_or_default();
+            challenge == ALL_CHALLENGES || app_state.challenges.get(challenge).is_some()
+        };
+        let Some(challenge) = self.linked_challenge.take() else {
+            return;
+        };
+        if known {
+            self.selected_challenge = challenge;
+        } else {
+            log::warn!("Ignoring unknown challenge from link: {}", challenge);
+            let message = format!("The link is for an unknown challenge: {}", challenge);
+            notifications::notify(ctx, Level::Warning, message);
+        }
+    }
+
+    fn share_link(&self) -> Option<String> {
+        let (url, _) = page_location()?;
+        Some(format!(
+            "{}?{}",
+            url,
+            share_query(&self.selected_challenge, self.filter, &self.sort_column)
+        ))
+    }
+
+    /// Clicking the column already sorted by flips it, any other starts ascending.
+    fn toggle_sort(&mut self, column: &str) {
+        if self.sort_column == column {
+            self.sort_descending = !self.sort_descending;
+        } else {
+            self.sort_column = column.to_string();
+            self.sort_descending = false;
+        }
+    }
+
+    /// Shows the error in the table, and as a toast unless it's the same one again,
+    /// which keeps auto refresh against a broken server from piling them up.
+    fn set_fetch_error(&mut self, ctx: &egui::Context, level: Level, error: String) {
+        if self.notified_error.as_ref() != Some(&error) {
+            notifications::notify(ctx, level, error.clone());
+            self.notified_error = Some(error.clone());
+        }
+        self.fetch_error = Some(error);
+    }
+
+    /// Keeps the way out right next to the problem, with the raw error tucked away.
+    fn fetch_error_ui(&mut self, ui: &mut egui::Ui, error: &str) {
+        let message = match self.scores {
+            Some(_) => "Some scores couldn't be loaded",
+            None => "Couldn't load the scores",
+        };
+        ui.group(|ui| {
+            ui.horizontal(|ui| {
+                ui.colored_label(ui.visuals().error_fg_color, format!("⚠ {}", message));
+                let busy = self.fetching();
+                if ui
+                    .add_enabled(!busy, egui::Button::new("🔄 Try again"))
+                    .clicked()
+                {
+                    self.refresh();
+                }
+            });
+            egui::CollapsingHeader::new("Details")
+                .id_source("scoreboard_error_details")
+                .default_open(false)
+                .show(ui, |ui| {
+                    ui.label(error);
+                });
+        });
+    }
+
+    fn table_ui(&mut self, ui: &mut egui::Ui) {
+        if self.check_fetch_promises(ui.ctx()) {
+            ui.horizontal(|ui| {
+                ui.add(egui::Spinner::new());
+                ui.label("Fetching scores...");
+            });
+        }
+
+        if let Some(seconds) = self.rate_limit_remaining() {
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                format!("Rate limited, retrying in {}s", seconds),
+            );
+        } else if let Some(e) = self.fetch_error.clone() {
+            self.fetch_error_ui(ui, &e);
+        }
+
+        if let Some(fetched_at) = self.offline_since {
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                format!(
+                    "Offline — showing cached data from {} ({})",
+                    fetched_at.format("%H:%M UTC"),
+                    time_ago((chrono::Utc::now() - fetched_at).num_seconds())
+                ),
+            );
+        }
+
+        if let Some(scores) = &self.scores {
+            if scores.is_empty() {
+                let message = match self.awaiting_challenges {
+                    true => "No challenges to show scores for",
+                    false => "No scores yet for this challenge",
+                };
+                ui.centered_and_justified(|ui| {
+                    ui.label(message);
+                });
+                return;
+            }
+        }
+
+        let text_height = egui::TextStyle::Body.resolve(ui.style()).size;
+        let row_height = self.density.row_height(text_height);
+        let all_challenges = self.selected_challenge == ALL_CHALLENGES;
+        let table_id = ("scoreboard_table", all_challenges, self.columns.generation);
+        // The tighter spacing is for the table only, not whatever comes after it
+        ui.push_id(table_id, |ui| {
+            ui.spacing_mut().item_spacing = self.density.item_spacing();
+            self.table_body_ui(ui, all_challenges, row_height)
+        });
+    }
+
+    fn page_markdown(&self) -> String {
+        let range = page_range(self.rows.len(), self.page, self.page_size);
+        let all_challenges = self.selected_challenge == ALL_CHALLENGES;
+        let options = ViewOptions {
+            first_rank: range.start,
+            formatting: self.formatting(),
+            show_challenge: all_challenges,
+            ..self.view_options()
+        };
+        to_markdown(&self.rows[range], &options)
+    }
+
+    fn table_body_ui(&mut self, ui: &mut egui::Ui, all_challenges: bool, row_height: f32) {
+        let range = page_range(self.rows.len(), self.page, self.page_size);
+        let options = ViewOptions {
+            first_rank: range.start,
+            player: self.player_name.clone(),
+            selected_rank: self.selected_row,
+            formatting: self.formatting(),
+            row_height: Some(row_height),
+            widths: self.columns.widths(all_challenges).to_vec(),
+            scroll_to_row: self.scroll_to_row.take(),
+            submitted: Some(&self.submitted),
+            show_challenge: all_challenges,
+            ..self.view_options()
+        };
+        let response = scoreboard_widget(ui, &self.rows[range], &options);
+
+        if let Some(column) = response.sort_clicked {
+            self.toggle_sort(column);
+        }
+        if let Some((index, score)) = response.clicked {
+            self.selected_row = Some(index);
+            self.details = Some(score);
+        }
+        if self.columns.set_widths(all_challenges, response.widths) {
+            self.columns_dirty = true;
+        }
+    }
+
+    /// Everything about one score, including the parts the table clips.
+    fn details_ui(&mut self, ctx: &egui::Context) {
+        let Some(score) = self.details.clone() else {
+            return;
+        };
+        let formatting = self.formatting();
+        let mut open = true;
+        egui::Window::new("Score details")
+            .open(&mut open)
+            .collapsible(false)
             .resizable(true)
-            .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
-            .column(Column::auto())
-            .column(Column::auto())
-            .column(Column::initial(100.0).range(40.0..=300.0))
-            .column(Column::initial(100.0).at_least(40.0).clip(true))
-            .column(Column::remainder())
-            .min_scrolled_height(0.0);
-
-        table
-            .header(20.0, |mut header| {
+            .default_width(300.0)
+            .show(ctx, |ui| {
+                egui::Grid::new("score_details")
+                    .num_columns(2)
+                    .spacing([20.0, 4.0])
+                    .show(ui, |ui| {
+                        ui.label("Name:");
+                        ui.label(&score.name);
+                        ui.end_row();
+
+                        ui.label("Language:");
+                        language_badge::language_label(ui, &score.language);
+                        ui.end_row();
+
+                        ui.label("Time:");
+                        ui.label(format!(
+                            "{} ({} ns)",
+                            NiceTime::new(score.time_ns),
+                            formatting.decimal(score.time_ns, 0)
+                        ));
+                        ui.end_row();
+
+                        let key = score_key(&score.name, &score.command, score.time_ns);
+                        if let Some(at) = self.submitted.get(&key) {
+                            ui.label("Submitted:");
+                            ui.label(format!(
+                                "{} ({})",
+                                formatting.date_time(at),
+                                time_ago((chrono::Utc::now() - *at).num_seconds())
+                            ));
+                            ui.end_row();
+                        }
+                    });
+                ui.separator();
+                ui.label("Binary:");
+                let mut command = score.command.as_str();
+                ui.add(
+                    egui::TextEdit::multiline(&mut command)
+                        .code_editor()
+                        .desired_rows(2)
+                        .desired_width(f32::INFINITY),
+                );
+            });
+        if !open {
+            self.details = None;
+        }
+    }
+}
+
+/// Where the keyboard selection goes for `key`, in a table of `len` rows.
+/// Nothing selected yet starts from the top.
+fn move_selection(current: Option<usize>, key: egui::Key, len: usize) -> Option<usize> {
+    if len == 0 {
+        return None;
+    }
+    let last = len - 1;
+    match (key, current) {
+        (egui::Key::Home, _) | (egui::Key::ArrowDown | egui::Key::ArrowUp, None) => Some(0),
+        (egui::Key::End, _) => Some(last),
+        (egui::Key::ArrowDown, Some(i)) => Some((i + 1).min(last)),
+        (egui::Key::ArrowUp, Some(i)) => Some(i.saturating_sub(1).min(last)),
+        (_, current) => current.map(|i| i.min(last)),
+    }
+}
+
+/// Like [`NiceTime`], but always the same width with two decimals,
+/// so times line up in a monospace column, e.g. "  1.20 ms" over " 13.40 µs".
+fn aligned_time(time_ns: f64, formatting: &Formatting) -> String {
+    const UNITS: [(f64, &str); 4] = [
+        (1.0, "ns"),
+        (1_000.0, "µs"),
+        (1_000_000.0, "ms"),
+        (1_000_000_000.0, "s"),
+    ];
+    // The unit goes by the value as it's shown, or 999.996 ns would round up to "1000.00 ns"
+    let shown = |value: f64| format!("{:.2}", value).parse().unwrap_or(value);
+    let (value, unit) = UNITS
+        .iter()
+        .map(|(scale, unit)| (time_ns / scale, *unit))
+        .find(|(value, _)| shown(*value) < 1_000.0)
+        .unwrap_or((time_ns / 1_000_000_000.0, "s"));
+    format!("{:>6} {:<2}", formatting.decimal(value, 2), unit)
+}
+
+/// Medals are only handed out when ranking by time.
+fn rank_label(rank: usize, medals: bool, formatting: &Formatting) -> String {
+    match (medals, rank) {
+        (true, 0) => "🥇".to_string(),
+        (true, 1) => "🥈".to_string(),
+        (true, 2) => "🥉".to_string(),
+        _ => formatting.count(rank),
+    }
+}
+
+fn is_player(player: &str, score: &Score) -> bool {
+    let player = player.trim();
+    !player.is_empty() && score.name.eq_ignore_ascii_case(player)
+}
+
+/// How [`scoreboard_widget`] shows a list of scores. Only the layout and the
+/// decorations come from here, the scores are shown in the order given, so
+/// sort and filter them first with [`ViewOptions::apply`].
+#[derive(Clone)]
+pub struct ViewOptions<'a> {
+    pub language: Option<Languages>,
+    pub filter: FilterOption,
+    pub sort_column: String,
+    pub sort_descending: bool,
+    /// Rank of the first score, for a page further down the board
+    pub first_rank: usize,
+    /// Scores by this player are highlighted
+    pub player: String,
+    pub selected_rank: Option<usize>,
+    pub formatting: Formatting,
+    /// Defaults to the height of a button
+    pub row_height: Option<f32>,
+    /// Column widths to start from, as handed back in [`TableResponse`]
+    pub widths: Vec<f32>,
+    /// Row of the given scores to bring into view
+    pub scroll_to_row: Option<usize>,
+    /// When each score was submitted, shown when hovering its time
+    pub submitted: Option<&'a HashMap<ScoreKey, chrono::DateTime<chrono::Utc>>>,
+    /// Adds a column for which challenge each score is from, see [`tag_challenge`]
+    pub show_challenge: bool,
+}
+
+impl Default for ViewOptions<'_> {
+    fn default() -> Self {
+        Self {
+            language: None,
+            filter: FilterOption::All,
+            sort_column: "time".to_string(),
+            sort_descending: false,
+            first_rank: 0,
+            player: String::new(),
+            selected_rank: None,
+            formatting: Formatting::default(),
+            row_height: None,
+            widths: Vec::new(),
+            scroll_to_row: None,
+            submitted: None,
+            show_challenge: false,
+        }
+    }
+}
+
+impl ViewOptions<'_> {
+    pub fn apply(&self, scores: &[Score]) -> Vec<Score> {
+        filter_scores(
+            scores,
+            self.language,
+            self.filter,
+            &self.sort_column,
+            self.sort_descending,
+        )
+    }
+}
+
+/// What the user did with a [`scoreboard_widget`]. It changes nothing itself.
+#[derive(Default)]
+pub struct TableResponse {
+    /// Sort column of the header that was clicked
+    pub sort_clicked: Option<&'static str>,
+    /// Rank and score of the row that was clicked
+    pub clicked: Option<(usize, Score)>,
+    /// Where the user left the column widths
+    pub widths: Vec<f32>,
+}
+
+/// Just the scoreboard's table, for showing scores anywhere without the fetching,
+/// filtering and paging of the [`ScoreBoardApp`] around it.
+pub fn scoreboard_widget(
+    ui: &mut egui::Ui,
+    scores: &[Score],
+    options: &ViewOptions<'_>,
+) -> TableResponse {
+    use egui_extras::TableBuilder;
+
+    let row_height = options.row_height.unwrap_or(ui.spacing().interact_size.y);
+    let with_challenge = options.show_challenge;
+    let mut table = TableBuilder::new(ui)
+        .striped(true)
+        .resizable(true)
+        .cell_layout(egui::Layout::left_to_right(egui::Align::Center));
+    for column in table_columns(with_challenge, &options.widths) {
+        table = table.column(column);
+    }
+    table = table.min_scrolled_height(0.0);
+    if let Some(row) = options.scroll_to_row {
+        table = table.scroll_to_row(row, Some(egui::Align::Center));
+    }
+
+    let mut response = TableResponse::default();
+    let sort_header = |ui: &mut egui::Ui, label: &str, column: &'static str| {
+        let text = match (options.sort_column == column, options.sort_descending) {
+            (true, false) => format!("{} ▲", label),
+            (true, true) => format!("{} ▼", label),
+            (false, _) => label.to_string(),
+        };
+        let button = egui::Button::new(egui::RichText::new(text).strong()).frame(false);
+        ui.add(button)
+            .on_hover_text("Sort by this, ties go by name and then time")
+            .clicked()
+            .then_some(column)
+    };
+
+    table
+        .header(20.0, |mut header| {
+            let mut widths = Vec::new();
+            let mut sort_clicked = None;
+            header.col(|ui| {
+                widths.push(ui.max_rect().width());
+                ui.strong("#");
+            });
+            header.col(|ui| {
+                widths.push(ui.max_rect().width());
+                sort_clicked = sort_clicked.or(sort_header(ui, "Time", "time"));
+            });
+            header.col(|ui| {
+                widths.push(ui.max_rect().width());
+                sort_clicked = sort_clicked.or(sort_header(ui, "Name", "name"));
+            });
+            header.col(|ui| {
+                widths.push(ui.max_rect().width());
+                sort_clicked = sort_clicked.or(sort_header(ui, "Language", "language"));
+            });
+            if with_challenge {
                 header.col(|ui| {
-                    ui.strong("#");
+                    widths.push(ui.max_rect().width());
+                    ui.strong("Challenge");
                 });
-                header.col(|ui| {
-                    ui.strong("Time");
+            }
+            header.col(|ui| {
+                sort_clicked = sort_clicked.or(sort_header(ui, "Binary", "binary"));
+            });
+            response.widths = widths;
+            response.sort_clicked = sort_clicked;
+        })
+        .body(|body| {
+            let offset = options.first_rank;
+            let medals = options.sort_column == "time" && !options.sort_descending;
+            let now = chrono::Utc::now();
+            let formatting = &options.formatting;
+
+            body.rows(row_height, scores.len(), |i, mut row| {
+                let score = &scores[i];
+                let key = score_key(&score.name, &score.command, score.time_ns);
+                let highlight = is_player(&options.player, score);
+                let selected = options.selected_rank == Some(offset + i);
+                let mut clicked = false;
+
+                row.col(|ui| {
+                    clicked |= row_cell(ui, highlight, selected, (offset + i, 0));
+                    let rank = offset + i;
+                    ui.label(rank_label(rank, medals, formatting))
+                        .on_hover_text(format!("Rank {}", formatting.count(rank)));
                 });
-                header.col(|ui| {
-                    ui.strong("Name");
+                row.col(|ui| {
+                    clicked |= row_cell(ui, highlight, selected, (offset + i, 1));
+                    let time =
+                        egui::RichText::new(aligned_time(score.time_ns, formatting)).monospace();
+                    let response = ui
+                        .with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            ui.label(time)
+                        })
+                        .inner;
+                    if let Some(at) = options.submitted.and_then(|s| s.get(&key)) {
+                        response.on_hover_text(format!(
+                            "Submitted {}",
+                            time_ago((now - *at).num_seconds())
+                        ));
+                    }
                 });
-                header.col(|ui| {
-                    ui.strong("Language");
+                row.col(|ui| {
+                    clicked |= row_cell(ui, highlight, selected, (offset + i, 2));
+                    ui.label(&score.name);
                 });
-                header.col(|ui| {
-                    ui.strong("Binary");
+                row.col(|ui| {
+                    clicked |= row_cell(ui, highlight, selected, (offset + i, 3));
+                    language_badge::language_label(ui, &score.language);
+                });
+                if options.show_challenge {
+                    row.col(|ui| {
+                        clicked |= row_cell(ui, highlight, selected, (offset + i, 5));
+                        ui.label(challenge_of(score));
+                    });
+                }
+                row.col(|ui| {
+                    clicked |= row_cell(ui, highlight, selected, (offset + i, 4));
+                    ui.label(&score.command);
                 });
+
+                if clicked {
+                    response.clicked = Some((offset + i, score.clone()));
+                }
+            });
+        });
+    response
+}
+
+/// Paints the row highlight and makes the cell clickable, returning whether it was clicked.
+/// The keyboard selection is outlined so it still shows on a highlighted row.
+fn row_cell(ui: &mut egui::Ui, highlight: bool, selected: bool, id: impl std::hash::Hash) -> bool {
+    if highlight {
+        ui.painter()
+            .rect_filled(ui.max_rect(), 0.0, ui.visuals().selection.bg_fill);
+    }
+    if selected {
+        let stroke = egui::Stroke::new(1.0, ui.visuals().selection.stroke.color);
+        ui.painter()
+            .hline(ui.max_rect().x_range(), ui.max_rect().top(), stroke);
+        ui.painter()
+            .hline(ui.max_rect().x_range(), ui.max_rect().bottom(), stroke);
+    }
+    ui.interact(
+        ui.max_rect(),
+        egui::Id::new(("score_cell", id)),
+        egui::Sense::click(),
+    )
+    .on_hover_cursor(egui::CursorIcon::PointingHand)
+    .clicked()
+}
+
+fn row_count_text(shown: usize, total: usize, formatting: &Formatting) -> String {
+    let noun = if total == 1 { "score" } else { "scores" };
+    if shown == total {
+        format!("Showing {} {}", formatting.count(total), noun)
+    } else {
+        format!(
+            "Showing {} of {} {}",
+            formatting.count(shown),
+            formatting.count(total),
+            noun
+        )
+    }
+}
+
+/// Keeps the scores whose name contains the query, ignoring case.
+fn search_scores(scores: Vec<Score>, query: &str) -> Vec<Score> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return scores;
+    }
+    scores
+        .into_iter()
+        .filter(|s| s.name.to_lowercase().contains(&query))
+        .collect()
+}
+
+/// Whether any of `scores` has a known submission time. Scores served from the cache
+/// may have none, as do those from backends that don't send them.
+fn has_submission_times(
+    scores: &[Score],
+    submitted: &HashMap<ScoreKey, chrono::DateTime<chrono::Utc>>,
+) -> bool {
+    scores
+        .iter()
+        .any(|s| submitted.contains_key(&score_key(&s.name, &s.command, s.time_ns)))
+}
+
+/// Keeps the scores submitted at or after `since`, dropping those with no known time.
+/// Done before the unique filters, so they pick the best within the range.
+fn submitted_since(
+    scores: &[Score],
+    submitted: &HashMap<ScoreKey, chrono::DateTime<chrono::Utc>>,
+    since: Option<chrono::DateTime<chrono::Utc>>,
+) -> Vec<Score> {
+    let Some(since) = since else {
+        return scores.to_vec();
+    };
+    scores
+        .iter()
+        .filter(|s| {
+            let key = score_key(&s.name, &s.command, s.time_ns);
+            submitted.get(&key).map_or(false, |at| *at >= since)
+        })
+        .cloned()
+        .collect()
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// What each field of a score in the JSON download means. Scripts may rely on
+/// these, so fields can be added but not renamed or removed.
+const JSON_FIELDS: [(&str, &str); 6] = [
+    ("rank", "Position in the table as shown, starting from 0"),
+    ("time_ns", "Run time in nanoseconds"),
+    ("time", "The run time, for reading"),
+    ("name", "Player name"),
+    ("language", "Language the entry is written in"),
+    ("command", "What the entry was run with"),
+];
+
+#[derive(serde::Serialize)]
+struct JsonScore<'a> {
+    rank: usize,
+    time_ns: f64,
+    time: String,
+    name: &'a str,
+    language: &'a str,
+    command: &'a str,
+}
+
+#[derive(serde::Serialize)]
+struct JsonExport<'a> {
+    challenge: &'a str,
+    fields: std::collections::BTreeMap<&'static str, &'static str>,
+    scores: Vec<JsonScore<'a>>,
+}
+
+/// Like [`to_csv`], wrapped up with the challenge and a description of the fields.
+fn to_json(challenge: &str, scores: &[Score]) -> String {
+    let export = JsonExport {
+        challenge,
+        fields: JSON_FIELDS.into_iter().collect(),
+        scores: scores
+            .iter()
+            .enumerate()
+            .map(|(rank, score)| JsonScore {
+                rank,
+                time_ns: score.time_ns,
+                time: NiceTime::new(score.time_ns).to_string(),
+                name: &score.name,
+                language: &score.language,
+                command: &score.command,
             })
-            .body(|mut body| {
-                if let Some(scores) = &self.scores {
-                    let mut filters = FilterBuilder::new();
-                    match self.filter {
-                        FilterOption::All => {}
-                        FilterOption::UniquePlayers => {
-                            filters.append(ScoreBoardFilter::UniquePlayers);
-                        }
-                        FilterOption::UniqueLanguage => {
-                            filters.append(ScoreBoardFilter::UniquePlayers);
-                        }
-                    };
+            .collect(),
+    };
+    serde_json::to_string_pretty(&export).unwrap_or_default()
+}
 
-                    filters.append(ScoreBoardFilter::Sort(
-                        SortColumn::from_str(self.sort_column.as_str()).expect("Invalid Cloumn"),
-                    ));
-                    let scores = ScoreBoard::new(scores.clone()).filter(filters).scores();
+/// Serializes the rows in the order and with the ranks shown in the table.
+fn to_csv(scores: &[Score]) -> String {
+    let mut csv = String::from("rank,time_ns,time,name,language,command\n");
+    for (i, score) in scores.iter().enumerate() {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{}\n",
+            i,
+            score.time_ns,
+            NiceTime::new(score.time_ns),
+            csv_field(&score.name),
+            csv_field(&score.language),
+            csv_field(&score.command),
+        ));
+    }
+    csv
+}
 
-                    for (i, score) in scores.iter().enumerate() {
-                        let time = NiceTime::new(score.time_ns);
-                        let name = score.name.clone();
-                        let language = score.language.clone();
-                        let binary = score.command.clone();
+/// Cells longer than this are cut short in the markdown table.
+const MARKDOWN_CELL_LENGTH: usize = 40;
 
-                        body.row(text_height, |mut row| {
-                            row.col(|ui| {
-                                ui.label(i.to_string());
-                            });
-                            row.col(|ui| {
-                                ui.label(time.to_string());
-                            });
-                            row.col(|ui| {
-                                ui.label(name);
-                            });
-                            row.col(|ui| {
-                                ui.label(language);
-                            });
-                            row.col(|ui| {
-                                ui.label(binary);
-                            });
-                        });
-                    }
-                }
-            });
+/// Keeps a cell on one line and from ending it early.
+fn markdown_cell(text: &str) -> String {
+    let text = text.replace(['\r', '\n'], " ");
+    let text = match text.chars().count() > MARKDOWN_CELL_LENGTH {
+        true => {
+            let cut: String = text.chars().take(MARKDOWN_CELL_LENGTH - 1).collect();
+            format!("{}…", cut.trim_end())
+        }
+        false => text,
+    };
+    text.replace('|', "\\|")
+}
+
+/// The scores as a GitHub flavoured markdown table, with the same columns and
+/// ranks as the scoreboard shows them.
+fn to_markdown(scores: &[Score], options: &ViewOptions<'_>) -> String {
+    let mut headers = vec!["#", "Time", "Name", "Language"];
+    if options.show_challenge {
+        headers.push("Challenge");
+    }
+    headers.push("Binary");
+
+    let mut markdown = format!("| {} |\n", headers.join(" | "));
+    let alignments: Vec<&str> = headers
+        .iter()
+        .map(|h| match *h {
+            "#" | "Time" => "--:",
+            _ => "---",
+        })
+        .collect();
+    markdown.push_str(&format!("| {} |\n", alignments.join(" | ")));
+
+    let medals = options.sort_column == "time" && !options.sort_descending;
+    for (i, score) in scores.iter().enumerate() {
+        let rank = options.first_rank + i;
+        let mut cells = vec![
+            rank_label(rank, medals, &options.formatting),
+            aligned_time(score.time_ns, &options.formatting)
+                .trim()
+                .to_string(),
+            markdown_cell(&score.name),
+            markdown_cell(&score.language),
+        ];
+        if options.show_challenge {
+            cells.push(markdown_cell(challenge_of(score)));
+        }
+        cells.push(markdown_cell(&score.command));
+        markdown.push_str(&format!("| {} |\n", cells.join(" | ")));
+    }
+    markdown
+}
+
+/// A page size of 0 from an older or hand edited save would leave nothing to page through.
+fn page_size_from_saved<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<usize, D::Error> {
+    let page_size = <usize as serde::Deserialize>::deserialize(deserializer)?;
+    Ok(page_size.max(1))
+}
+
+fn page_count(total: usize, page_size: usize) -> usize {
+    ((total + page_size - 1) / page_size).max(1)
+}
+
+/// The slice of rows which falls on the given page.
+fn page_range(total: usize, page: usize, page_size: usize) -> std::ops::Range<usize> {
+    let start = (page * page_size).min(total);
+    let end = (start + page_size).min(total);
+    start..end
+}
+
+/// Narrows the scores down to one language, if one is picked, before handing
+/// them to [`apply_view`]. An unknown sort column falls back to time.
+fn filter_scores(
+    scores: &[Score],
+    language: Option<Languages>,
+    filter: FilterOption,
+    sort_column: &str,
+    descending: bool,
+) -> Vec<Score> {
+    let scores: Vec<Score> = match language {
+        Some(language) => {
+            let language = language.to_string();
+            scores
+                .iter()
+                .filter(|s| s.language.eq_ignore_ascii_case(&language))
+                .cloned()
+                .collect()
+        }
+        None => scores.to_vec(),
+    };
+    let sort = SortColumn::from_str(sort_column).unwrap_or(SortColumn::Time);
+    apply_view(&scores, filter, sort, descending)
+}
+
+/// Settles ties on the sort column, so equal entries keep their places from one
+/// fetch to the next: by name, then fastest first, then by binary and language.
+fn tiebreak(a: &Score, b: &Score) -> std::cmp::Ordering {
+    a.name
+        .cmp(&b.name)
+        .then(a.time_ns.total_cmp(&b.time_ns))
+        .then_with(|| a.command.cmp(&b.command))
+        .then_with(|| a.language.cmp(&b.language))
+}
+
+/// Applies the selected filter on top of the sorted scores.
+///
+/// Sorting happens first so that the unique filters keep the top ranked entry.
+fn apply_view(
+    scores: &[Score],
+    filter: FilterOption,
+    sort: SortColumn,
+    descending: bool,
+) -> Vec<Score> {
+    // The column sort is stable, so sorting by the tiebreak first decides ties.
+    // Descending flips everything afterwards, so ties go in reverse to start with.
+    let mut scores = scores.to_vec();
+    scores.sort_by(|a, b| match descending {
+        false => tiebreak(a, b),
+        true => tiebreak(b, a),
+    });
+    let sort = FilterBuilder::new().add_filter(ScoreBoardFilter::Sort(sort));
+    let mut scores = ScoreBoard::new(scores).filter(sort).scores();
+    if descending {
+        scores.reverse();
+    }
+
+    let mut filters = FilterBuilder::new();
+    match filter {
+        FilterOption::All => {}
+        FilterOption::UniquePlayers => {
+            filters.append(ScoreBoardFilter::UniquePlayers);
+        }
+        FilterOption::UniqueLanguage => {
+            filters.append(ScoreBoardFilter::UniqueLanguages);
+        }
+    };
+    ScoreBoard::new(scores).filter(filters).scores()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::format::NumberFormat;
+    use crate::helpers::ChallengeCollection;
+
+    fn score(name: &str, time_ns: f64, language: &str) -> Score {
+        Score::new(name, "./run", time_ns, String::new(), language)
+    }
+
+    fn fixture() -> Vec<Score> {
+        vec![
+            Score::new("carol", "./c", 20.0, String::new(), "Go"),
+            Score::new("alice", "./b", 30.0, String::new(), "Rust"),
+            Score::new("bob", "./d", 10.0, String::new(), "Python"),
+            Score::new("alice", "./a", 5.0, String::new(), "C"),
+        ]
+    }
+
+    fn names(scores: &[Score]) -> Vec<&str> {
+        scores.iter().map(|s| s.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_apply_view_all() {
+        let view = apply_view(&fixture(), FilterOption::All, SortColumn::Time, false);
+        assert_eq!(names(&view), vec!["alice", "bob", "carol", "alice"]);
+    }
+
+    #[test]
+    fn test_apply_view_unique_players() {
+        let view = apply_view(
+            &fixture(),
+            FilterOption::UniquePlayers,
+            SortColumn::Time,
+            false,
+        );
+        assert_eq!(names(&view), vec!["alice", "bob", "carol"]);
+        assert_eq!(view[0].time_ns, 5.0);
+    }
+
+    #[test]
+    fn test_apply_view_unique_language() {
+        let mut scores = fixture();
+        scores.push(Score::new("dave", "./e", 1.0, String::new(), "Go"));
+        let view = apply_view(
+            &scores,
+            FilterOption::UniqueLanguage,
+            SortColumn::Time,
+            false,
+        );
+        assert_eq!(names(&view), vec!["dave", "alice", "bob", "alice"]);
+    }
+
+    #[test]
+    fn test_apply_view_sort_columns() {
+        let sorted = |sort| {
+            apply_view(&fixture(), FilterOption::All, sort, false)
+                .into_iter()
+                .map(|s| s.command)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(sorted(SortColumn::Time), vec!["./a", "./d", "./c", "./b"]);
+        // Both of alice's entries tie on the name, the faster one goes first
+        assert_eq!(
+            sorted(SortColumn::PlayerName),
+            vec!["./a", "./b", "./d", "./c"]
+        );
+        assert_eq!(sorted(SortColumn::Binary), vec!["./a", "./b", "./c", "./d"]);
+        assert_eq!(
+            sorted(SortColumn::Language),
+            vec!["./a", "./c", "./d", "./b"]
+        );
+    }
+
+    #[test]
+    fn test_ties_are_stable() {
+        let tied = vec![
+            score("carol", 10.0, "Go"),
+            score("alice", 10.0, "Rust"),
+            Score::new("bob", "./b", 10.0, String::new(), "C"),
+            Score::new("bob", "./a", 10.0, String::new(), "C"),
+            score("dave", 5.0, "Go"),
+        ];
+        let mut reordered = tied.clone();
+        reordered.reverse();
+        reordered.swap(0, 3);
+
+        let view = |scores: &[Score], descending| {
+            apply_view(scores, FilterOption::All, SortColumn::Time, descending)
+                .into_iter()
+                .map(|s| format!("{} {}", s.name, s.command))
+                .collect::<Vec<_>>()
+        };
+        let ascending = view(&tied, false);
+        assert_eq!(
+            ascending,
+            vec![
+                "dave ./run",
+                "alice ./run",
+                "bob ./a",
+                "bob ./b",
+                "carol ./run"
+            ]
+        );
+        assert_eq!(view(&reordered, false), ascending);
+
+        // Flipping the column keeps the ties in the same order
+        let descending = view(&tied, true);
+        assert_eq!(
+            descending,
+            vec![
+                "alice ./run",
+                "bob ./a",
+                "bob ./b",
+                "carol ./run",
+                "dave ./run"
+            ]
+        );
+        assert_eq!(view(&reordered, true), descending);
+    }
+
+    #[test]
+    fn test_unknown_sort_column_falls_back_to_time() {
+        let view = filter_scores(&fixture(), None, FilterOption::All, "shoe_size", false);
+        assert_eq!(view[0].time_ns, 5.0);
+    }
+
+    #[test]
+    fn test_unique_language_keeps_fastest() {
+        let scores = vec![
+            score("alice", 30.0, "Rust"),
+            score("bob", 10.0, "Python"),
+            score("alice", 5.0, "Rust"),
+            score("carol", 20.0, "Python"),
+            score("dave", 40.0, "Go"),
+        ];
+
+        let filtered = filter_scores(&scores, None, FilterOption::UniqueLanguage, "time", false);
+
+        assert_eq!(filtered.len(), 3);
+        assert_eq!(filtered[0].language, "Rust");
+        assert_eq!(filtered[0].time_ns, 5.0);
+        assert_eq!(filtered[1].language, "Python");
+        assert_eq!(filtered[1].name, "bob");
+        assert_eq!(filtered[2].language, "Go");
+    }
+
+    #[test]
+    fn test_sort_descending() {
+        let scores = vec![
+            score("alice", 30.0, "Rust"),
+            score("bob", 10.0, "Python"),
+            score("carol", 20.0, "Go"),
+        ];
+
+        let filtered = filter_scores(&scores, None, FilterOption::All, "name", true);
+
+        let names: Vec<&str> = filtered.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["carol", "bob", "alice"]);
+    }
+
+    #[test]
+    fn test_language_filter() {
+        let scores = vec![
+            score("alice", 30.0, "Rust"),
+            score("bob", 10.0, "Python"),
+            score("carol", 20.0, "rust"),
+        ];
+
+        let filtered = filter_scores(
+            &scores,
+            Some(Languages::Rust),
+            FilterOption::All,
+            "time",
+            false,
+        );
+
+        let names: Vec<&str> = filtered.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["carol", "alice"]);
+    }
+
+    #[test]
+    fn test_search_by_name() {
+        let scores = vec![
+            score("Alice", 30.0, "Rust"),
+            score("bob", 10.0, "Python"),
+            score("malice", 20.0, "Go"),
+        ];
+
+        let found = search_scores(scores.clone(), "ALI");
+        let names: Vec<&str> = found.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["Alice", "malice"]);
+
+        assert_eq!(search_scores(scores.clone(), "").len(), 3);
+        assert!(search_scores(scores, "carol").is_empty());
+    }
+
+    #[test]
+    fn test_row_count_text() {
+        assert_eq!(
+            row_count_text(12, 340, &Formatting::default()),
+            "Showing 12 of 340 scores"
+        );
+        assert_eq!(
+            row_count_text(340, 340, &Formatting::default()),
+            "Showing 340 scores"
+        );
+        assert_eq!(
+            row_count_text(1, 1, &Formatting::default()),
+            "Showing 1 score"
+        );
+        let grouped = Formatting {
+            numbers: NumberFormat::Comma,
+            ..Default::default()
+        };
+        assert_eq!(
+            row_count_text(5, 12_000, &grouped),
+            "Showing 5 of 12,000 scores"
+        );
+    }
+
+    #[test]
+    fn test_csv_export() {
+        let scores = vec![
+            score("alice", 1500.0, "Rust"),
+            Score::new("bob", "python \"a,b\".py", 2.0, String::new(), "Python"),
+        ];
+
+        let csv = to_csv(&scores);
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines[0], "rank,time_ns,time,name,language,command");
+        assert_eq!(lines[1], "0,1500,1.500us,alice,Rust,./run");
+        assert_eq!(lines[2], "1,2,2.000ns,bob,Python,\"python \"\"a,b\"\".py\"");
+    }
+
+    #[test]
+    fn test_markdown_export() {
+        let long = format!("./{}", "x".repeat(60));
+        let mut scores = vec![
+            Score::new("a|b", &long, 1500.0, String::new(), "Rust"),
+            score("bob", 2.0, "Python"),
+        ];
+        let options = ViewOptions {
+            first_rank: 10,
+            ..Default::default()
+        };
+        let markdown = to_markdown(&scores, &options);
+        let lines: Vec<&str> = markdown.lines().collect();
+
+        assert_eq!(lines[0], "| # | Time | Name | Language | Binary |");
+        assert_eq!(lines[1], "| --: | --: | --- | --- | --- |");
+        assert_eq!(
+            lines[2],
+            format!("| 10 | 1.50 µs | a\\|b | Rust | ./{}… |", "x".repeat(37))
+        );
+        assert_eq!(lines[3], "| 11 | 2.00 ns | bob | Python | ./run |");
+
+        tag_challenge(&mut scores[1], "2331");
+        let options = ViewOptions {
+            show_challenge: true,
+            ..Default::default()
+        };
+        let markdown = to_markdown(&scores, &options);
+        let lines: Vec<&str> = markdown.lines().collect();
+        assert_eq!(
+            lines[0],
+            "| # | Time | Name | Language | Challenge | Binary |"
+        );
+        assert_eq!(lines[3], "| 🥈 | 2.00 ns | bob | Python | 2331 | ./run |");
+    }
+
+    #[test]
+    fn test_json_export() {
+        let scores = vec![score("alice", 1500.0, "Rust"), score("bob", 2.0, "Python")];
+        let json: serde_json::Value = serde_json::from_str(&to_json("2331", &scores)).unwrap();
+
+        assert_eq!(json["challenge"], "2331");
+        assert_eq!(json["scores"][1]["rank"], 1);
+        assert_eq!(json["scores"][1]["name"], "bob");
+        assert_eq!(json["scores"][0]["time_ns"], 1500.0);
+        assert_eq!(json["scores"][0]["time"], "1.500us");
+        // Every field is described, and nothing is left undescribed
+        let fields = json["fields"].as_object().unwrap();
+        let exported = json["scores"][0].as_object().unwrap();
+        assert_eq!(fields.len(), exported.len());
+        assert!(exported.keys().all(|k| fields.contains_key(k)));
+    }
+
+    #[test]
+    fn test_rank_medals() {
+        assert_eq!(rank_label(0, true, &Formatting::default()), "🥇");
+        assert_eq!(rank_label(2, true, &Formatting::default()), "🥉");
+        assert_eq!(rank_label(3, true, &Formatting::default()), "3");
+        assert_eq!(rank_label(0, false, &Formatting::default()), "0");
+    }
+
+    #[test]
+    fn test_late_response_is_not_shown() {
+        let mut app = ScoreBoardApp {
+            selected_challenge: "B".to_string(),
+            ..Default::default()
+        };
+        app.apply_scores("B", vec![score("bob", 10.0, "Python")]);
+        app.apply_scores("A", vec![score("alice", 30.0, "Rust")]);
+
+        let shown: Vec<&str> = app.rows.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(shown, vec!["bob"]);
+        assert!(app.cached_scores("A").is_some());
+    }
+
+    #[test]
+    fn test_partial_failure_shows_what_succeeded() {
+        let mut app = ScoreBoardApp {
+            selected_challenge: ALL_CHALLENGES.to_string(),
+            pending: PendingFetch {
+                challenge: ALL_CHALLENGES.to_string(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let body = r#"[{"name":"alice","command":"./a","time_ns":1.0,"language":"Rust"}]"#;
+        app.record_result("2331", Ok(serde_json::from_str(body).unwrap()));
+        app.record_result("2333", Ok(serde_json::from_str(body).unwrap()));
+        app.record_result("2332", Err(FetchError::Status(500, String::new())));
+        app.finish_fetch(&egui::Context::default());
+
+        // The same score for two challenges still says which is which
+        let challenges: Vec<&str> = app.rows.iter().map(challenge_of).collect();
+        assert_eq!(challenges, vec!["2331", "2333"]);
+        assert_eq!(
+            app.fetch_error.as_deref(),
+            Some("Couldn't load scores for 2332")
+        );
+    }
+
+    #[test]
+    fn test_all_challenges_without_challenges() {
+        let mut app = ScoreBoardApp {
+            selected_challenge: ALL_CHALLENGES.to_string(),
+            ..Default::default()
+        };
+        assert!(!app.fetch());
+        assert!(app.awaiting_challenges);
+        assert!(!app.fetching());
+        assert_eq!(app.scores, Some(Vec::new()));
+    }
+
+    #[test]
+    fn test_offline_falls_back_to_cache() {
+        let mut app = ScoreBoardApp {
+            selected_challenge: "B".to_string(),
+            ..Default::default()
+        };
+        let ctx = egui::Context::default();
+        app.apply_scores("B", vec![score("bob", 10.0, "Python")]);
+        assert!(app.offline_since.is_none());
+
+        app.pending.challenge = "B".to_string();
+        app.record_result(
+            "B",
+            Err(FetchError::Network("Unable to reach server".to_string())),
+        );
+        app.finish_fetch(&ctx);
+        assert!(app.offline_since.is_some());
+        assert!(app.fetch_error.is_none());
+        assert_eq!(app.rows.len(), 1);
+
+        app.apply_scores("B", vec![score("bob", 9.0, "Python")]);
+        assert!(app.offline_since.is_none());
+
+        app.pending.challenge = "B".to_string();
+        app.record_result("B", Err(FetchError::RateLimited(30)));
+        app.finish_fetch(&ctx);
+        assert!(app.offline_since.is_none());
+        assert_eq!(
+            app.fetch_error.as_deref(),
+            Some("Failed to fetch scores: Rate limited, retry in 30s")
+        );
+    }
+
+    #[test]
+    fn test_column_layout() {
+        let mut layout = ColumnLayout::default();
+        assert!(layout.set_widths(false, vec![30.0, 60.0, 120.0, 100.0]));
+        assert!(!layout.set_widths(false, vec![30.2, 60.0, 120.0, 100.0]));
+        assert!(layout.widths(true).is_empty());
+        assert_eq!(table_columns(false, layout.widths(false)).len(), 5);
+        assert_eq!(table_columns(true, layout.widths(true)).len(), 6);
+
+        layout.reset();
+        assert_eq!(layout.generation, 1);
+        assert!(layout.widths(false).is_empty());
+    }
+
+    #[test]
+    fn test_move_selection() {
+        use egui::Key;
+        assert_eq!(move_selection(None, Key::ArrowDown, 5), Some(0));
+        assert_eq!(move_selection(Some(0), Key::ArrowDown, 5), Some(1));
+        assert_eq!(move_selection(Some(4), Key::ArrowDown, 5), Some(4));
+        assert_eq!(move_selection(Some(0), Key::ArrowUp, 5), Some(0));
+        assert_eq!(move_selection(Some(3), Key::Home, 5), Some(0));
+        assert_eq!(move_selection(Some(1), Key::End, 5), Some(4));
+        assert_eq!(move_selection(Some(9), Key::ArrowUp, 5), Some(4));
+        assert_eq!(move_selection(Some(2), Key::ArrowDown, 0), None);
+    }
+
+    #[test]
+    fn test_aligned_time() {
+        assert_eq!(aligned_time(999.0, &Formatting::default()), "999.00 ns");
+        assert_eq!(aligned_time(13_400.0, &Formatting::default()), " 13.40 µs");
+        assert_eq!(
+            aligned_time(1_200_000.0, &Formatting::default()),
+            "  1.20 ms"
+        );
+        assert_eq!(aligned_time(2.5e9, &Formatting::default()), "  2.50 s ");
+        // Right at the boundaries, the unit follows the rounding
+        assert_eq!(aligned_time(999.994, &Formatting::default()), "999.99 ns");
+        assert_eq!(aligned_time(999.996, &Formatting::default()), "  1.00 µs");
+        assert_eq!(aligned_time(999_999.0, &Formatting::default()), "  1.00 ms");
+        assert_eq!(
+            aligned_time(999_999_999.0, &Formatting::default()),
+            "  1.00 s "
+        );
+        let widths: Vec<usize> = [5.0, 5e3, 5e6, 5e9]
+            .iter()
+            .map(|t| aligned_time(*t, &Formatting::default()).chars().count())
+            .collect();
+        assert_eq!(widths, vec![9; 4]);
+        let comma = Formatting {
+            numbers: NumberFormat::Dot,
+            ..Default::default()
+        };
+        assert_eq!(aligned_time(13_400.0, &comma), " 13,40 µs");
+    }
+
+    #[test]
+    fn test_submission_times() {
+        let body = r#"[
+            {"name":"alice","command":"./a","time_ns":1.0,"language":"Rust","submitted_at":"2023-11-01T10:00:00Z"},
+            {"name":"bob","command":"./b","time_ns":2.0,"language":"Go"}
+        ]"#;
+        let fetched: Vec<FetchedScore> = serde_json::from_str(body).unwrap();
+        assert_eq!(fetched[1].score.name, "bob");
+        let times = submission_times(&fetched);
+        assert_eq!(times.len(), 1);
+        assert!(times.contains_key(&score_key("alice", "./a", 1.0)));
+    }
+
+    #[test]
+    fn test_date_range() {
+        let at = |s: &str| chrono::DateTime::parse_from_rfc3339(s).unwrap().into();
+        let now = at("2023-11-02T10:00:00Z"); // a Thursday
+        assert_eq!(DateRange::AllTime.since(now), None);
+        assert_eq!(
+            DateRange::ThisWeek.since(now),
+            Some(at("2023-10-30T00:00:00Z"))
+        );
+
+        let scores = fixture();
+        let submitted = HashMap::from([
+            (score_key("carol", "./c", 20.0), at("2023-11-02T09:00:00Z")),
+            (score_key("alice", "./b", 30.0), at("2023-10-20T09:00:00Z")),
+            (score_key("alice", "./a", 5.0), at("2023-10-31T09:00:00Z")),
+        ]);
+        assert!(has_submission_times(&scores, &submitted));
+        assert!(!has_submission_times(&scores[2..3], &submitted));
+        assert!(!has_submission_times(&scores, &HashMap::new()));
+        let since = |range: DateRange| submitted_since(&scores, &submitted, range.since(now));
+        assert_eq!(names(&since(DateRange::AllTime)).len(), 4);
+        assert_eq!(names(&since(DateRange::Last24Hours)), vec!["carol"]);
+        assert_eq!(names(&since(DateRange::ThisWeek)), vec!["carol", "alice"]);
+        assert_eq!(
+            names(&since(DateRange::Last30Days)),
+            vec!["carol", "alice", "alice"]
+        );
+    }
+
+    #[test]
+    fn test_share_link_round_trip() {
+        let query = share_query("2331 b&c", FilterOption::UniquePlayers, "name");
+        let params = parse_share_query(&format!("?{}", query));
+        assert_eq!(
+            params,
+            ShareParams {
+                challenge: Some("2331 b&c".to_string()),
+                filter: Some(FilterOption::UniquePlayers),
+                sort_column: Some("name".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_invalid_share_params_are_ignored() {
+        let params = parse_share_query("?challenge=&filter=bogus&sort=shoe_size&x=%zz&junk");
+        assert_eq!(params, ShareParams::default());
+        assert_eq!(parse_share_query(""), ShareParams::default());
+    }
+
+    #[test]
+    fn test_unknown_linked_challenge_is_ignored() {
+        let mut app = ScoreBoardApp {
+            selected_challenge: "2331".to_string(),
+            ..Default::default()
+        };
+        let ctx = egui::Context::default();
+        app.apply_share_params(parse_share_query("?challenge=9999"));
+        app.resolve_linked_challenge(&ctx);
+        assert_eq!(app.linked_challenge.as_deref(), Some("9999"));
+
+        {
+            let mut app_state = app.app_state.lock().unwrap();
+            app_state.challenges = ChallengeCollection::from_json(
+                r#"[
+                    {"command": "2331", "name": "a", "table": "t", "doc": ""},
+                    {"command": "2332", "name": "b", "table": "t", "doc": ""}
+                ]"#,
+            );
+            app_state.challenges_loaded = true;
+        }
+        app.resolve_linked_challenge(&ctx);
+        assert_eq!(app.selected_challenge, "2331");
+        assert!(app.linked_challenge.is_none());
+
+        app.apply_share_params(parse_share_query("?challenge=2332"));
+        app.resolve_linked_challenge(&ctx);
+        assert_eq!(app.selected_challenge, "2332");
+    }
+
+    #[test]
+    fn test_pagination() {
+        assert_eq!(page_count(0, 25), 1);
+        assert_eq!(page_count(25, 25), 1);
+        assert_eq!(page_count(26, 25), 2);
+
+        assert_eq!(page_range(60, 0, 25), 0..25);
+        assert_eq!(page_range(60, 2, 25), 50..60);
+        assert_eq!(page_range(60, 5, 25), 60..60);
+
+        let mut saved = serde_json::to_value(ScoreBoardApp::default()).unwrap();
+        saved["page_size"] = 0.into();
+        let app: ScoreBoardApp = serde_json::from_value(saved).unwrap();
+        assert_eq!(app.page_size, 1);
+        assert_eq!(page_count(10, app.page_size), 10);
     }
 }
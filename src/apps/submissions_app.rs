@@ -0,0 +1,261 @@
+use crate::helpers::{
+    fetchers::{RequestStatus, Requestor},
+    format,
+    submission::{self, PastSubmission, Submission, SubmissionResult},
+    AppState, LoginState,
+};
+use std::sync::{Arc, Mutex};
+
+#[derive(serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct SubmissionsApp {
+    selected_challenge: String,
+    #[serde(skip)]
+    active_challenge: Option<String>,
+    #[serde(skip)]
+    submissions: Vec<PastSubmission>,
+    #[serde(skip)]
+    error: Option<String>,
+    #[serde(skip)]
+    fetcher: Option<Requestor>,
+    /// `AppState::submissions_accepted` as of the last fetch it triggered
+    #[serde(skip)]
+    seen_submissions: u64,
+    #[serde(skip)]
+    app_state: Arc<Mutex<AppState>>,
+}
+
+impl Default for SubmissionsApp {
+    fn default() -> Self {
+        Self {
+            selected_challenge: "".to_string(),
+            active_challenge: None,
+            submissions: Vec::new(),
+            error: None,
+            fetcher: None,
+            seen_submissions: 0,
+            app_state: Arc::new(Mutex::new(AppState::default())),
+        }
+    }
+}
+
+impl SubmissionsApp {
+    fn fetch(&mut self) {
+        self.active_challenge = Some(self.selected_challenge.clone());
+        self.submissions.clear();
+        self.error = None;
+        if self.selected_challenge.is_empty() {
+            self.fetcher = None;
+            return;
+        }
+
+        log::debug!("Fetching submissions for {}", self.selected_challenge);
+        let url = self
+            .app_state
+            .lock()
+            .unwrap()
+            .api_url(&format!("game/submissions/{}", self.selected_challenge));
+        let app_state = Arc::clone(&self.app_state);
+        let mut getter = Requestor::new_get(app_state, &url, true);
+        getter.send();
+        self.fetcher = Some(getter);
+    }
+
+    fn refresh(&mut self) {
+        self.app_state
+            .clone()
+            .lock()
+            .unwrap()
+            .update_activity_timer();
+        self.fetch();
+    }
+
+    fn check_fetch_promise(&mut self) -> bool {
+        let Some(getter) = &mut self.fetcher else {
+            return false;
+        };
+        match getter.check_promise() {
+            RequestStatus::Success(text) => {
+                self.fetcher = None;
+                match submission::parse_submissions(&text) {
+                    Ok(submissions) => self.submissions = submissions,
+                    Err(e) => self.error = Some(e),
+                }
+            }
+            RequestStatus::Failed(e) => {
+                self.fetcher = None;
+                self.error = Some(format!("Failed to fetch submissions: {}", e));
+            }
+            _ => {}
+        }
+        self.fetcher.is_some()
+    }
+
+    /// Hands the submission over to the code editor, which picks it up and opens.
+    fn reopen(&self, submission: &Submission) {
+        self.app_state.lock().unwrap().reopen_submission = Some(submission.clone());
+    }
+
+    fn table_ui(&mut self, ui: &mut egui::Ui) {
+        use egui_extras::{Column, TableBuilder};
+
+        let text_height = egui::TextStyle::Body.resolve(ui.style()).size;
+        let now = chrono::Utc::now();
+        let mut reopen = None;
+
+        TableBuilder::new(ui)
+            .striped(true)
+            .resizable(true)
+            .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+            .column(Column::auto())
+            .column(Column::auto())
+            .column(Column::auto())
+            .column(Column::auto())
+            .column(Column::initial(100.0).at_least(40.0).clip(true))
+            .column(Column::remainder())
+            .min_scrolled_height(0.0)
+            .header(20.0, |mut header| {
+                for title in ["Submitted", "Status", "Score", "Language", "File", ""] {
+                    header.col(|ui| {
+                        ui.strong(title);
+                    });
+                }
+            })
+            .body(|body| {
+                body.rows(text_height + 8.0, self.submissions.len(), |i, mut row| {
+                    let past = &self.submissions[i];
+                    row.col(|ui| {
+                        match past.submitted_at {
+                            Some(at) => ui.label(format::time_ago((now - at).num_seconds())),
+                            None => ui.label("-"),
+                        };
+                    });
+                    row.col(|ui| {
+                        let color = match past.result {
+                            SubmissionResult::Success { .. } => egui::Color32::GREEN,
+                            SubmissionResult::Failure { .. } => ui.visuals().error_fg_color,
+                            _ => ui.visuals().warn_fg_color,
+                        };
+                        ui.colored_label(color, past.status())
+                            .on_hover_text(past.result.to_string());
+                    });
+                    row.col(|ui| {
+                        ui.label(past.score());
+                    });
+                    row.col(|ui| {
+                        ui.label(past.submission.language.to_string());
+                    });
+                    row.col(|ui| {
+                        ui.label(&past.submission.filename);
+                    });
+                    row.col(|ui| {
+                        if past.submission.code.is_some()
+                            && ui
+                                .button("Open")
+                                .on_hover_text("Open this code in the code editor")
+                                .clicked()
+                        {
+                            reopen = Some(past.submission.clone());
+                        }
+                    });
+                });
+            });
+
+        if let Some(submission) = reopen {
+            self.reopen(&submission);
+        }
+    }
+}
+
+impl super::App for SubmissionsApp {
+    fn name(&self) -> &'static str {
+        "📜 My Submissions"
+    }
+
+    fn set_app_state_ref(&mut self, app_state: Arc<Mutex<AppState>>) {
+        self.app_state = app_state;
+    }
+
+    fn show(&mut self, ctx: &egui::Context, open: &mut bool) {
+        let challenges_differ = match self.active_challenge.clone() {
+            None => true,
+            Some(active) => active != self.selected_challenge,
+        };
+        let accepted = self.app_state.lock().unwrap().submissions_accepted;
+        if *open && (challenges_differ || accepted != self.seen_submissions) {
+            self.seen_submissions = accepted;
+            self.fetch();
+        }
+
+        if *open && super::refresh_shortcut_pressed(ctx, self.name()) {
+            self.refresh();
+        }
+
+        if let Some(fetcher) = &mut self.fetcher {
+            if fetcher.refresh_context() {
+                ctx.request_repaint();
+            }
+        }
+
+        egui::Window::new(self.name())
+            .open(open)
+            .default_width(500.0)
+            .default_height(400.0)
+            .vscroll(false)
+            .resizable(true)
+            .show(ctx, |ui| {
+                use super::View as _;
+                self.ui(ui);
+            });
+    }
+}
+
+impl super::View for SubmissionsApp {
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_label("Challenge")
+                .selected_text(self.selected_challenge.clone())
+                .show_ui(ui, |ui| {
+                    ui.style_mut().wrap = Some(false);
+                    ui.set_min_width(60.0);
+
+                    for challenge in self.app_state.lock().unwrap().challenges.items.iter() {
+                        ui.selectable_value(
+                            &mut self.selected_challenge,
+                            challenge.command.clone(),
+                            &challenge.command,
+                        );
+                    }
+                });
+            if ui.button("Refresh").on_hover_text("Shortcut: R").clicked() {
+                self.refresh();
+            }
+        });
+        ui.separator();
+
+        let in_progress = self.check_fetch_promise();
+        if let LoginState::LoggedOut = self.app_state.lock().unwrap().logged_in {
+            if !in_progress {
+                ui.label("Log in to see your submissions");
+                return;
+            }
+        }
+        if in_progress {
+            ui.horizontal(|ui| {
+                ui.add(egui::Spinner::new());
+                ui.label("Fetching submissions...");
+            });
+        }
+        if let Some(e) = &self.error {
+            ui.colored_label(ui.visuals().error_fg_color, e);
+        }
+
+        if self.selected_challenge.is_empty() {
+            ui.label("Pick a challenge to see what you've submitted");
+        } else if !in_progress && self.error.is_none() && self.submissions.is_empty() {
+            ui.label("Nothing submitted for this challenge yet");
+        } else {
+            egui::ScrollArea::horizontal().show(ui, |ui| self.table_ui(ui));
+        }
+    }
+}
@@ -9,21 +9,20 @@ enum State {
     Dirty,
     Fetching,
     Clean,
+    Failed,
 }
 
 pub struct ChallengeFetcher {
     state: State,
+    /// Where the challenges were last fetched from, to notice the backend changing
+    url: String,
     info_fetcher: Option<Requestor>,
     app_state: Arc<Mutex<AppState>>,
 }
 
 impl Default for ChallengeFetcher {
     fn default() -> Self {
-        Self {
-            info_fetcher: None,
-            state: State::Dirty,
-            app_state: Arc::new(Mutex::new(AppState::default())),
-        }
+        Self::new(Arc::new(Mutex::new(AppState::default())))
     }
 }
 
@@ -32,11 +31,19 @@ impl ChallengeFetcher {
         Self {
             info_fetcher: None,
             state: State::Dirty,
+            url: String::new(),
             app_state: app_state.clone(),
         }
     }
 
     pub fn tick(&mut self) {
+        if self.state == State::Failed && self.app_state.lock().unwrap().challenges_error.is_none()
+        {
+            self.state = State::Dirty;
+        }
+        if self.state != State::Dirty && self.url != self.app_state.lock().unwrap().backend_url {
+            self.state = State::Dirty;
+        }
         self.fetch();
         self.check_info_promise();
     }
@@ -49,7 +56,11 @@ impl ChallengeFetcher {
         self.state = State::Fetching;
         let app_state = self.app_state.clone();
         let my_app_state = self.app_state.clone();
-        self.info_fetcher = my_app_state.lock().unwrap().challenges.fetch(app_state);
+        let mut my_app_state = my_app_state.lock().unwrap();
+        self.url = my_app_state.backend_url.clone();
+        my_app_state.challenges_fetching = true;
+        let url = my_app_state.api_url("game/challenge");
+        self.info_fetcher = my_app_state.challenges.fetch(&url, app_state);
     }
     fn check_info_promise(&mut self) {
         if self.state != State::Fetching {
@@ -67,14 +78,21 @@ impl ChallengeFetcher {
                     log::debug!("Challenge info fetch success: {}", data);
                     self.info_fetcher = None;
                     self.state = State::Clean;
+                    let mut app_state = self.app_state.lock().unwrap();
+                    app_state.challenges = ChallengeCollection::from_json(data);
+                    app_state.challenges_error = None;
+                    app_state.challenges_fetching = false;
+                    app_state.challenges_loaded = true;
                 }
-                RequestStatus::Failed(_) => {
+                RequestStatus::Failed(e) => {
+                    log::error!("Challenge info fetch failed: {}", e);
                     self.info_fetcher = None;
-                    self.state = State::Dirty;
+                    self.state = State::Failed;
+                    let mut app_state = self.app_state.lock().unwrap();
+                    app_state.challenges_error = Some(e.to_string());
+                    app_state.challenges_fetching = false;
                 }
             }
-            self.app_state.lock().unwrap().challenges =
-                ChallengeCollection::from_json(&result.to_string());
         }
     }
 }
@@ -0,0 +1,195 @@
+use crate::helpers::{
+    fetchers::{RequestStatus, Requestor},
+    visibility, AppState,
+};
+use std::sync::{Arc, Mutex};
+
+/// Small and needs no login, so it's cheap to poll
+const HEALTH_ENDPOINT: &str = "game/languages";
+/// Says whether the backend is down for maintenance, checked along with the health
+const STATUS_ENDPOINT: &str = "game/status";
+const DEFAULT_MAINTENANCE_MESSAGE: &str = "Submissions are temporarily disabled";
+const POLL_SECONDS: f64 = 30.0;
+const TIMEOUT_SECONDS: u32 = 10;
+/// Answers slower than this count as slow
+const SLOW_SECONDS: f64 = 2.0;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Health {
+    Unknown,
+    Healthy,
+    Slow(f64),
+    Unreachable(String),
+}
+
+/* Pings the backend every so often, so users can see it's
+down before they wonder why the scoreboard won't load */
+pub struct HealthCheck {
+    health: Health,
+    /// The backend last pinged, a new one is pinged straight away
+    url: String,
+    fetcher: Option<Requestor>,
+    status_fetcher: Option<Requestor>,
+    /// egui time the current ping went out
+    sent_at: f64,
+    next_check: f64,
+    app_state: Arc<Mutex<AppState>>,
+}
+
+impl Default for HealthCheck {
+    fn default() -> Self {
+        Self::new(Arc::new(Mutex::new(AppState::default())))
+    }
+}
+
+impl HealthCheck {
+    pub fn new(app_state: Arc<Mutex<AppState>>) -> Self {
+        Self {
+            health: Health::Unknown,
+            url: String::new(),
+            fetcher: None,
+            status_fetcher: None,
+            sent_at: 0.0,
+            next_check: 0.0,
+            app_state,
+        }
+    }
+
+    pub fn health(&self) -> &Health {
+        &self.health
+    }
+
+    pub fn tick(&mut self, ctx: &egui::Context) {
+        let now = ctx.input(|i| i.time);
+        if self.url != self.app_state.lock().unwrap().backend_url {
+            self.fetcher = None;
+            self.status_fetcher = None;
+            self.health = Health::Unknown;
+            self.app_state.lock().unwrap().maintenance = None;
+            self.next_check = now;
+        }
+        if self.fetcher.is_none() && now >= self.next_check {
+            self.ping(now);
+        }
+        self.check_promise(now);
+        self.check_status();
+
+        if let Some(fetcher) = &mut self.status_fetcher {
+            if fetcher.refresh_context() {
+                ctx.request_repaint();
+            }
+        }
+        match &mut self.fetcher {
+            Some(fetcher) => {
+                if fetcher.refresh_context() {
+                    ctx.request_repaint();
+                }
+            }
+            // The next check can wait for the tab to come back
+            None if visibility::page_hidden() => {}
+            None => ctx.request_repaint_after(std::time::Duration::from_secs_f64(
+                (self.next_check - now).max(0.0),
+            )),
+        }
+    }
+
+    fn ping(&mut self, now: f64) {
+        let (url, status_url) = {
+            let app_state = self.app_state.lock().unwrap();
+            self.url = app_state.backend_url.clone();
+            (
+                app_state.api_url(HEALTH_ENDPOINT),
+                app_state.api_url(STATUS_ENDPOINT),
+            )
+        };
+        let mut getter =
+            Requestor::new_get(self.app_state.clone(), &url, false).with_timeout(TIMEOUT_SECONDS);
+        getter.send();
+        self.fetcher = Some(getter);
+        self.sent_at = now;
+
+        if self.status_fetcher.is_none() {
+            let mut getter = Requestor::new_get(self.app_state.clone(), &status_url, false)
+                .with_timeout(TIMEOUT_SECONDS);
+            getter.send();
+            self.status_fetcher = Some(getter);
+        }
+    }
+
+    fn check_status(&mut self) {
+        let Some(getter) = &mut self.status_fetcher else {
+            return;
+        };
+        let maintenance = match getter.check_promise() {
+            RequestStatus::Success(body) => maintenance_message(&body),
+            // Backends without the endpoint never go into maintenance
+            RequestStatus::Failed(e) => {
+                log::debug!("Status check failed: {}", e);
+                None
+            }
+            _ => return,
+        };
+        self.status_fetcher = None;
+        self.app_state.lock().unwrap().maintenance = maintenance;
+    }
+
+    fn check_promise(&mut self, now: f64) {
+        let Some(getter) = &mut self.fetcher else {
+            return;
+        };
+        self.health = match getter.check_promise() {
+            RequestStatus::Success(_) => {
+                let elapsed = now - self.sent_at;
+                match elapsed > SLOW_SECONDS {
+                    true => Health::Slow(elapsed),
+                    false => Health::Healthy,
+                }
+            }
+            RequestStatus::Failed(e) => {
+                log::warn!("Health check failed: {}", e);
+                Health::Unreachable(e)
+            }
+            _ => return,
+        };
+        self.fetcher = None;
+        self.next_check = now + POLL_SECONDS;
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct Status {
+    #[serde(default)]
+    maintenance: bool,
+    message: Option<String>,
+}
+
+/// What to tell users if the status says the backend is in maintenance.
+fn maintenance_message(body: &str) -> Option<String> {
+    let status: Status = serde_json::from_str(body).ok()?;
+    status.maintenance.then(|| {
+        status
+            .message
+            .filter(|m| !m.trim().is_empty())
+            .unwrap_or(DEFAULT_MAINTENANCE_MESSAGE.to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_maintenance_message() {
+        assert_eq!(maintenance_message(r#"{"maintenance": false}"#), None);
+        assert_eq!(maintenance_message("{}"), None);
+        assert_eq!(maintenance_message("not json"), None);
+        assert_eq!(
+            maintenance_message(r#"{"maintenance": true}"#),
+            Some(DEFAULT_MAINTENANCE_MESSAGE.to_string())
+        );
+        assert_eq!(
+            maintenance_message(r#"{"maintenance": true, "message": "Back at 14:00"}"#),
+            Some("Back at 14:00".to_string())
+        );
+    }
+}
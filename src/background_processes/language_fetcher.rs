@@ -0,0 +1,92 @@
+use crate::helpers::{
+    fetchers::{RequestStatus, Requestor},
+    AppState, Languages,
+};
+use std::sync::{Arc, Mutex};
+
+#[derive(PartialEq, Clone, Copy)]
+enum State {
+    Dirty,
+    Fetching,
+    Done,
+}
+
+/* Asks the backend which languages it accepts.
+Until that succeeds, the built-in list in AppState is used */
+pub struct LanguageFetcher {
+    state: State,
+    /// Where the languages were last fetched from, to notice the backend changing
+    url: String,
+    fetcher: Option<Requestor>,
+    app_state: Arc<Mutex<AppState>>,
+}
+
+impl Default for LanguageFetcher {
+    fn default() -> Self {
+        Self::new(Arc::new(Mutex::new(AppState::default())))
+    }
+}
+
+impl LanguageFetcher {
+    pub fn new(app_state: Arc<Mutex<AppState>>) -> Self {
+        Self {
+            state: State::Dirty,
+            url: String::new(),
+            fetcher: None,
+            app_state,
+        }
+    }
+
+    pub fn tick(&mut self) {
+        if self.state == State::Done && self.url != self.app_state.lock().unwrap().backend_url {
+            self.state = State::Dirty;
+        }
+        self.fetch();
+        self.check_promise();
+    }
+
+    fn fetch(&mut self) {
+        if self.state != State::Dirty {
+            return;
+        }
+        log::debug!("Fetching languages");
+        self.state = State::Fetching;
+        let url = {
+            let app_state = self.app_state.lock().unwrap();
+            self.url = app_state.backend_url.clone();
+            app_state.api_url("game/languages")
+        };
+        let mut getter = Requestor::new_get(self.app_state.clone(), &url, true);
+        getter.send();
+        self.fetcher = Some(getter);
+    }
+
+    fn check_promise(&mut self) {
+        if self.state != State::Fetching {
+            return;
+        }
+
+        if let Some(getter) = &mut self.fetcher {
+            match getter.check_promise() {
+                RequestStatus::NotStarted => {}
+                RequestStatus::InProgress => {}
+                RequestStatus::Success(data) => {
+                    self.fetcher = None;
+                    self.state = State::Done;
+                    match Languages::list_from_json(&data) {
+                        Some(languages) => {
+                            log::debug!("Backend accepts {} languages", languages.len());
+                            self.app_state.lock().unwrap().languages = languages;
+                        }
+                        None => log::warn!("No usable languages in {}, using defaults", data),
+                    }
+                }
+                RequestStatus::Failed(e) => {
+                    log::warn!("Language fetch failed, using defaults: {}", e);
+                    self.fetcher = None;
+                    self.state = State::Done;
+                }
+            }
+        }
+    }
+}
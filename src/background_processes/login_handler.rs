@@ -1,11 +1,16 @@
-use crate::helpers::{refresh, AppState};
+use crate::components::notifications::{self, Level};
+use crate::helpers::{refresh, AppState, LoginState};
 use std::sync::{Arc, Mutex};
 
+/// How long to wait before trying again when the backend couldn't be reached
+const NETWORK_RETRY_SECONDS: i64 = 30;
+
 #[derive(PartialEq)]
 enum State {
     Idle,
     Fetching,
     Error,
+    Offline,
 }
 
 pub struct LoginFetcher {
@@ -33,33 +38,70 @@ impl LoginFetcher {
         }
     }
 
-    pub fn tick(&mut self) {
+    pub fn tick(&mut self, ctx: &egui::Context) {
         self.fetch();
-        self.check_info_promise();
+        self.check_info_promise(ctx);
     }
 
     fn fetch(&mut self) {
         if !self.app_state.lock().unwrap().needs_refresh() {
             return;
         }
-        self.app_state.lock().unwrap().last_refresh = chrono::Utc::now().time();
+        {
+            let mut app_state = self.app_state.lock().unwrap();
+            app_state.last_refresh = chrono::Utc::now().time();
+            // Forget the old expiry so we don't refresh again while this one is in flight
+            app_state.token_expires = None;
+        }
         log::debug!("Refreshing token");
         self.state = State::Fetching;
-        self.token_refresh_promise = refresh::submit_refresh();
+        let app_state = self.app_state.lock().unwrap();
+        self.token_refresh_promise =
+            refresh::submit_refresh(&app_state.api_url("auth/refresh"), app_state.auth());
     }
 
-    fn check_info_promise(&mut self) {
+    fn check_info_promise(&mut self, ctx: &egui::Context) {
         match refresh::check_refresh_promise(&mut self.token_refresh_promise) {
             refresh::RefreshStatus::NotStarted => {}
             refresh::RefreshStatus::InProgress => {}
-            refresh::RefreshStatus::Success => {
+            refresh::RefreshStatus::Success { lifetime, token } => {
                 AppState::set_logged_in(&self.app_state);
+                AppState::set_token_lifetime(&self.app_state, lifetime);
+                if let Some(token) = token {
+                    AppState::set_access_token(&self.app_state, token);
+                }
                 self.state = State::Idle;
             }
-            refresh::RefreshStatus::Failed(_) => {
+            refresh::RefreshStatus::Failed(e) => {
+                // Only worth mentioning if this ends a session the user had
+                {
+                    let mut app_state = self.app_state.lock().unwrap();
+                    if let LoginState::LoggedIn = app_state.logged_in {
+                        app_state.session_expired = true;
+                        notifications::notify(
+                            ctx,
+                            Level::Warning,
+                            format!("Session expired, please log in again ({})", e),
+                        );
+                    }
+                }
                 AppState::set_logged_out(&self.app_state);
                 self.state = State::Error;
             }
+            refresh::RefreshStatus::NetworkError(e) => {
+                // The session may well still be good, so stay logged in and try again soon
+                if self.state != State::Offline {
+                    notifications::notify(
+                        ctx,
+                        Level::Warning,
+                        format!("Unable to refresh your session, retrying ({})", e),
+                    );
+                }
+                let mut app_state = self.app_state.lock().unwrap();
+                app_state.last_refresh = chrono::Utc::now().time() - app_state.refresh_period
+                    + chrono::Duration::seconds(NETWORK_RETRY_SECONDS);
+                self.state = State::Offline;
+            }
         }
     }
 }
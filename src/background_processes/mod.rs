@@ -1,4 +1,8 @@
 mod challenge_fetcher;
 pub use challenge_fetcher::ChallengeFetcher;
+mod language_fetcher;
+pub use language_fetcher::LanguageFetcher;
 mod login_handler;
 pub use login_handler::LoginFetcher;
+mod health_check;
+pub use health_check::{Health, HealthCheck};
@@ -1,7 +1,11 @@
+use crate::components::{
+    code_input,
+    confirm_submission::{self, Choice},
+};
 use crate::helpers::{
     fetchers::Requestor,
-    submission::{Submission, SubmissionResult},
-    AppState, Languages,
+    submission::{self, FailureReason, Submission, SubmissionResult},
+    AppState,
 };
 use egui::*;
 use egui_commonmark::*;
@@ -19,8 +23,6 @@ pub struct CodeEditor {
     instructions: String,
     label: String,
 
-    #[serde(skip)]
-    url: String,
     #[serde(skip)]
     last_result: SubmissionResult,
     #[serde(skip)]
@@ -32,6 +34,11 @@ pub struct CodeEditor {
 
     #[serde(skip)]
     submitter: Option<Requestor>,
+    /// Whether the submission `submitter` has out is only a test run
+    #[serde(skip)]
+    submitted_test: bool,
+    #[serde(skip)]
+    confirm_scored: bool,
     #[serde(skip)]
     pub app_state: Arc<Mutex<AppState>>,
 }
@@ -46,12 +53,11 @@ impl Default for CodeEditor {
             instructions: "No Challenge Loaded".into(),
             label: "Code Editor".into(),
 
-            url: option_env!("BACKEND_URL")
-                .unwrap_or("http://123.4.5.6:3000/")
-                .to_string(),
             last_result: SubmissionResult::NotStarted,
             toasts: Toasts::default(),
             submitter: None,
+            submitted_test: false,
+            confirm_scored: false,
             active_challenge: None,
             selected_challenge: "".into(),
             app_state: Arc::new(Mutex::new(AppState::default())),
@@ -60,20 +66,22 @@ impl Default for CodeEditor {
 }
 
 impl CodeEditor {
-    fn submit(&mut self) {
+    fn submit(&mut self, ctx: &egui::Context) {
+        submission::remember_language(ctx, &self.run);
         let submission = self.run.clone();
-        let url = format!("{}api/game/submit", self.url);
+        let url = self.app_state.lock().unwrap().api_url("game/submit");
         let app_state = Arc::clone(&self.app_state);
+        self.submitted_test = submission.test;
         self.submitter = submission.sender(app_state, &url);
     }
 }
 
 impl CodeEditor {
     pub fn panels(&mut self, ctx: &egui::Context) {
-        let submission = Submission::check_sender(&mut self.submitter);
+        let submission = Submission::check_sender(&mut self.submitter, self.submitted_test);
         match submission {
             SubmissionResult::NotStarted => {}
-            SubmissionResult::Success { score: _, message } => {
+            SubmissionResult::Success { message, .. } => {
                 self.toasts
                     .info(format!("Result: {}", message))
                     .set_duration(Some(Duration::from_secs(5)));
@@ -85,6 +93,17 @@ impl CodeEditor {
 
         self.toasts.show(ctx);
 
+        if self.confirm_scored {
+            match confirm_submission::confirm_submission(ctx, &self.run) {
+                Choice::Undecided => {}
+                Choice::Submit => {
+                    self.confirm_scored = false;
+                    self.submit(ctx);
+                }
+                Choice::Cancel => self.confirm_scored = false,
+            }
+        }
+
         egui::TopBottomPanel::bottom("code_editor_bottom").show(ctx, |_ui| {
             let _layout = egui::Layout::top_down(egui::Align::Center).with_main_justify(true);
         });
@@ -119,12 +138,14 @@ impl CodeEditor {
                 ui.collapsing("Theme", |ui| {
                     ui.group(|ui| {
                         self.theme.ui(ui);
+                        code_input::tab_width_ui(ui);
                     });
                 });
             });
             ui.end_row();
 
             ui.horizontal(|ui| {
+                let previous_challenge = self.selected_challenge.clone();
                 egui::ComboBox::from_label("Challenge")
                     .selected_text(&self.selected_challenge)
                     .show_ui(ui, |ui| {
@@ -139,13 +160,18 @@ impl CodeEditor {
                             );
                         }
                     });
+                if self.selected_challenge != previous_challenge {
+                    self.run.language =
+                        submission::last_language(ui.ctx(), &self.selected_challenge);
+                }
 
                 ui.separator();
 
                 egui::ComboBox::from_label("Language")
                     .selected_text(format!("{}", self.run.language))
                     .show_ui(ui, |ui| {
-                        for l in Languages::iter() {
+                        let languages = self.app_state.lock().unwrap().languages.clone();
+                        for l in languages {
                             ui.selectable_value(&mut self.run.language, l, format!("{}", l));
                         }
                     });
@@ -163,7 +189,14 @@ impl CodeEditor {
                     .on_hover_text("What would you like this to be called on the scoreboard?");
                 });
                 ui.separator();
-                if ui.button("Submit").clicked() {
+                // Scores can still be looked at during maintenance, but nothing can be sent
+                let maintenance = self.app_state.lock().unwrap().maintenance.clone();
+                let down = maintenance.clone().unwrap_or_default();
+                if ui
+                    .add_enabled(maintenance.is_none(), egui::Button::new("Submit"))
+                    .on_disabled_hover_text(&down)
+                    .clicked()
+                {
                     self.app_state
                         .clone()
                         .lock()
@@ -174,18 +207,29 @@ impl CodeEditor {
                     self.run.code = Some(self.code.clone());
                     self.run.challenge = Some(self.selected_challenge.clone());
                     match self.run.validate() {
+                        Ok(_) if confirm_submission::needs_confirmation(ui.ctx(), &self.run) => {
+                            self.confirm_scored = true;
+                        }
                         Ok(_) => {
-                            self.submit();
+                            self.submit(ui.ctx());
                         }
                         Err(e) => {
                             self.toasts
                                 .error(format!("Invalid Submission: {}", e))
                                 .set_duration(Some(Duration::from_secs(5)));
-                            self.last_result = SubmissionResult::Failure { message: e };
+                            self.last_result = SubmissionResult::Failure {
+                                reason: FailureReason::Invalid,
+                                message: e,
+                                output: None,
+                            };
                         }
                     }
                 }
-                if ui.button("Test").clicked() {
+                if ui
+                    .add_enabled(maintenance.is_none(), egui::Button::new("Test"))
+                    .on_disabled_hover_text(&down)
+                    .clicked()
+                {
                     self.app_state
                         .clone()
                         .lock()
@@ -197,7 +241,7 @@ impl CodeEditor {
                     match self.run.validate() {
                         Ok(_) => {
                             log::debug!("Testing code");
-                            self.submit();
+                            self.submit(ui.ctx());
                         }
                         Err(e) => {
                             self.toasts
@@ -205,7 +249,11 @@ impl CodeEditor {
                                 .set_duration(Some(Duration::from_secs(5)));
 
                             log::error!("Validation Error: {}", e);
-                            self.last_result = SubmissionResult::Failure { message: e };
+                            self.last_result = SubmissionResult::Failure {
+                                reason: FailureReason::Invalid,
+                                message: e,
+                                output: None,
+                            };
                         }
                     }
                 }
@@ -215,7 +263,7 @@ impl CodeEditor {
 
         if self.show_instructions {
             ui.columns(2, |columns| {
-                ScrollArea::vertical()
+                ScrollArea::both()
                     .id_source("source")
                     .show(&mut columns[0], |ui| self.editor_ui(ui));
                 ScrollArea::vertical()
@@ -223,32 +271,19 @@ impl CodeEditor {
                     .show(&mut columns[1], |ui| self.instructions_ui(ui));
             });
         } else {
-            ScrollArea::vertical()
+            ScrollArea::both()
                 .id_source("source")
                 .show(ui, |ui| self.editor_ui(ui));
         }
     }
 
     fn editor_ui(&mut self, ui: &mut egui::Ui) {
-        let mut layouter = |ui: &egui::Ui, string: &str, wrap_width: f32| {
-            let mut layout_job = egui_extras::syntax_highlighting::highlight(
-                ui.ctx(),
-                &self.theme,
-                string,
-                &self.run.language.to_string(),
-            );
-            layout_job.wrap.max_width = wrap_width;
-            ui.fonts(|f| f.layout_job(layout_job))
-        };
-
-        ui.add(
-            egui::TextEdit::multiline(&mut self.code)
-                .font(egui::TextStyle::Monospace) // for cursor height
-                .code_editor()
-                .desired_rows(10)
-                .lock_focus(true)
-                .desired_width(f32::INFINITY)
-                .layouter(&mut layouter),
+        code_input::code_input(
+            ui,
+            "source_code",
+            &mut self.code,
+            self.run.language,
+            &self.theme,
         );
     }
 
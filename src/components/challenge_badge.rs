@@ -0,0 +1,50 @@
+use crate::helpers::Difficulty;
+use egui::Color32;
+
+/// Green to red, mid-tones so the dark text on top reads in both themes.
+pub fn difficulty_color(difficulty: Difficulty) -> Color32 {
+    match difficulty {
+        Difficulty::Easy => Color32::from_rgb(0x6c, 0xc0, 0x5c),
+        Difficulty::Medium => Color32::from_rgb(0xe8, 0xb0, 0x3c),
+        Difficulty::Hard => Color32::from_rgb(0xe0, 0x60, 0x58),
+    }
+}
+
+/// A small coloured tag with the difficulty on it, nothing when there isn't one.
+pub fn difficulty_badge(ui: &mut egui::Ui, difficulty: Option<Difficulty>) {
+    let Some(difficulty) = difficulty else {
+        return;
+    };
+    let text = egui::RichText::new(format!(" {} ", difficulty))
+        .small()
+        .color(Color32::BLACK)
+        .background_color(difficulty_color(difficulty));
+    ui.label(text);
+}
+
+/// Heads up each group of challenges in a selector. The uncategorised ones only
+/// get a heading when there are other groups to tell them apart from.
+pub fn category_heading(ui: &mut egui::Ui, category: Option<&str>, only_group: bool) {
+    match category {
+        Some(category) => {
+            ui.label(egui::RichText::new(category).small().weak());
+        }
+        None if !only_group => {
+            ui.label(egui::RichText::new("Other").small().weak());
+        }
+        None => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_colors_are_distinct() {
+        let colors = [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard].map(difficulty_color);
+        assert_ne!(colors[0], colors[1]);
+        assert_ne!(colors[1], colors[2]);
+        assert_ne!(colors[0], colors[2]);
+    }
+}
@@ -0,0 +1,233 @@
+use crate::helpers::Languages;
+use egui::text::{CCursor, CCursorRange};
+use egui::text_edit::TextEditState;
+use egui_extras::syntax_highlighting::{self, CodeTheme};
+
+pub const DEFAULT_TAB_WIDTH: usize = 4;
+
+fn tab_width_id() -> egui::Id {
+    egui::Id::new("code_input_tab_width")
+}
+
+/// How many columns Tab indents by, kept in egui's persisted memory.
+pub fn tab_width(ctx: &egui::Context) -> usize {
+    ctx.data_mut(|d| d.get_persisted(tab_width_id()))
+        .unwrap_or(DEFAULT_TAB_WIDTH)
+}
+
+pub fn tab_width_ui(ui: &mut egui::Ui) {
+    let mut width = tab_width(ui.ctx());
+    ui.horizontal(|ui| {
+        ui.label("Tab width:");
+        if ui
+            .add(egui::DragValue::new(&mut width).clamp_range(1..=8))
+            .changed()
+        {
+            ui.ctx()
+                .data_mut(|d| d.insert_persisted(tab_width_id(), width));
+        }
+    });
+}
+
+fn byte_index(text: &str, char_index: usize) -> usize {
+    text.char_indices()
+        .nth(char_index)
+        .map_or(text.len(), |(i, _)| i)
+}
+
+/// With nothing selected, adds spaces at the cursor up to the next tab stop.
+/// Otherwise every line the selection touches moves over by `width` spaces,
+/// leaving out the last one if the selection stops right at its start.
+/// Returns the new `start` and `end`, as char indices like the ones passed in.
+fn indent(text: &mut String, start: usize, end: usize, width: usize) -> (usize, usize) {
+    let from = byte_index(text, start);
+    if start == end {
+        let column = text[..from]
+            .chars()
+            .rev()
+            .take_while(|c| *c != '\n')
+            .count();
+        let spaces = width - column % width;
+        text.insert_str(from, &" ".repeat(spaces));
+        return (start + spaces, start + spaces);
+    }
+
+    let to = byte_index(text, end);
+    let first_line = text[..from].rfind('\n').map_or(0, |i| i + 1);
+    let mut line_starts = vec![first_line];
+    line_starts.extend(
+        text[first_line..to]
+            .match_indices('\n')
+            .map(|(i, _)| first_line + i + 1)
+            .filter(|i| *i < to),
+    );
+    let spaces = " ".repeat(width);
+    for line_start in line_starts.iter().rev() {
+        text.insert_str(*line_start, &spaces);
+    }
+    (start + width, end + width * line_starts.len())
+}
+
+/// Takes a tab, or up to `width` spaces, off the start of the line `cursor` is on.
+fn dedent(text: &mut String, cursor: usize, width: usize) -> usize {
+    let at = byte_index(text, cursor);
+    let line_start = text[..at].rfind('\n').map_or(0, |i| i + 1);
+    let column = text[line_start..at].chars().count();
+    let line = &text[line_start..];
+    let remove = match line.starts_with('\t') {
+        true => 1,
+        false => line.chars().take(width).take_while(|c| *c == ' ').count(),
+    };
+    text.replace_range(line_start..line_start + remove, "");
+    cursor - remove.min(column)
+}
+
+/// Editable, highlighted code with line numbers down the left. Tab and Shift+Tab
+/// indent and outdent with spaces instead of moving focus. Long lines aren't wrapped,
+/// so put it in a `ScrollArea` that scrolls both ways; the numbers scroll with it.
+pub fn code_input(
+    ui: &mut egui::Ui,
+    id_source: impl std::hash::Hash,
+    code: &mut String,
+    language: Languages,
+    theme: &CodeTheme,
+) -> egui::Response {
+    let id = ui.make_persistent_id(id_source);
+    if ui.memory(|m| m.has_focus(id)) {
+        handle_tab(ui, id, code);
+    }
+
+    let mut layouter = |ui: &egui::Ui, string: &str, _wrap_width: f32| {
+        let layout_job =
+            syntax_highlighting::highlight(ui.ctx(), theme, string, language.extension());
+        ui.fonts(|f| f.layout_job(layout_job))
+    };
+
+    let font = egui::TextStyle::Monospace.resolve(ui.style());
+    let lines = code.split('\n').count();
+    let digits = lines.to_string().len().max(2);
+    let char_width = ui.fonts(|f| f.glyph_width(&font, '0'));
+    let gutter = digits as f32 * char_width + 8.0;
+
+    ui.horizontal_top(|ui| {
+        ui.add_space(gutter);
+        let output = egui::TextEdit::multiline(code)
+            .id(id)
+            .code_editor()
+            .desired_rows(10)
+            .desired_width(f32::INFINITY)
+            .layouter(&mut layouter)
+            .show(ui);
+
+        let painter = ui.painter();
+        let color = ui.visuals().weak_text_color();
+        let right = output.response.rect.left() - 4.0;
+        let mut line = 1;
+        let mut line_start = true;
+        for row in &output.galley.rows {
+            if line_start {
+                let top = output.text_draw_pos.y + row.rect.top();
+                painter.text(
+                    egui::pos2(right, top),
+                    egui::Align2::RIGHT_TOP,
+                    line,
+                    font.clone(),
+                    color,
+                );
+                line += 1;
+            }
+            line_start = row.ends_with_newline;
+        }
+        output.response
+    })
+    .inner
+}
+
+fn handle_tab(ui: &egui::Ui, id: egui::Id, code: &mut String) {
+    let (tab, shift_tab) = ui.input_mut(|i| {
+        (
+            i.consume_key(egui::Modifiers::NONE, egui::Key::Tab),
+            i.consume_key(egui::Modifiers::SHIFT, egui::Key::Tab),
+        )
+    });
+    if !tab && !shift_tab {
+        return;
+    }
+    let Some(mut state) = TextEditState::load(ui.ctx(), id) else {
+        return;
+    };
+    let Some(range) = state.ccursor_range() else {
+        return;
+    };
+    let width = tab_width(ui.ctx());
+    let start = range.primary.index.min(range.secondary.index);
+    let end = range.primary.index.max(range.secondary.index);
+    let range = match tab {
+        true => {
+            let (start, end) = indent(code, start, end, width);
+            // The selection stays as it was, just shifted along with the text
+            let (primary, secondary) = match range.primary.index < range.secondary.index {
+                true => (start, end),
+                false => (end, start),
+            };
+            CCursorRange::two(CCursor::new(secondary), CCursor::new(primary))
+        }
+        false => CCursorRange::one(CCursor::new(dedent(code, range.primary.index, width))),
+    };
+    state.set_ccursor_range(Some(range));
+    state.store(ui.ctx(), id);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_indent_to_tab_stop() {
+        let mut text = "fn main() {\n}".to_string();
+        assert_eq!(indent(&mut text, 12, 12, 4), (16, 16));
+        assert_eq!(text, "fn main() {\n    }");
+
+        // Part way into a line only goes as far as the next stop
+        let mut text = "ab".to_string();
+        assert_eq!(indent(&mut text, 2, 2, 4), (4, 4));
+        assert_eq!(text, "ab  ");
+
+        // Chars before the cursor count as one column each
+        let mut text = "é".to_string();
+        assert_eq!(indent(&mut text, 1, 1, 2), (2, 2));
+        assert_eq!(text, "é ");
+    }
+
+    #[test]
+    fn test_indent_selection() {
+        // Part of a line still indents the whole of it, and the selection is kept
+        let mut text = "é selected".to_string();
+        assert_eq!(indent(&mut text, 2, 10, 2), (4, 12));
+        assert_eq!(text, "  é selected");
+
+        let mut text = "a\nb\nc".to_string();
+        assert_eq!(indent(&mut text, 0, 3, 4), (4, 11));
+        assert_eq!(text, "    a\n    b\nc");
+
+        // A selection ending at the start of a line leaves that line alone
+        let mut text = "a\nb\nc".to_string();
+        assert_eq!(indent(&mut text, 1, 4, 4), (5, 12));
+        assert_eq!(text, "    a\n    b\nc");
+    }
+
+    #[test]
+    fn test_dedent() {
+        let mut text = "x\n      y".to_string();
+        assert_eq!(dedent(&mut text, 9, 4), 5);
+        assert_eq!(text, "x\n  y");
+
+        let mut text = "\tz".to_string();
+        assert_eq!(dedent(&mut text, 0, 4), 0);
+        assert_eq!(text, "z");
+
+        let mut text = "nothing".to_string();
+        assert_eq!(dedent(&mut text, 3, 4), 3);
+        assert_eq!(text, "nothing");
+    }
+}
@@ -0,0 +1,21 @@
+use crate::helpers::Languages;
+use egui_extras::syntax_highlighting::{self, CodeTheme};
+
+/// Read-only view of some code, highlighted for `language` using the code theme
+/// picked in the editor. Without a language it's shown as plain monospace.
+pub fn code_view(ui: &mut egui::Ui, code: &str, language: Option<Languages>) -> egui::Response {
+    let theme = CodeTheme::from_memory(ui.ctx());
+    match language.map(|l| l.extension()) {
+        // syntect knows every language we support by its file extension
+        Some(extension) => syntax_highlighting::code_view_ui(ui, &theme, code, extension),
+        None => {
+            let mut code = code;
+            ui.add(
+                egui::TextEdit::multiline(&mut code)
+                    .code_editor()
+                    .desired_rows(1)
+                    .desired_width(f32::INFINITY),
+            )
+        }
+    }
+}
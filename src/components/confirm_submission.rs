@@ -0,0 +1,100 @@
+use crate::helpers::submission::Submission;
+
+/// What the user made of the confirmation dialog.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Choice {
+    Undecided,
+    Submit,
+    Cancel,
+}
+
+fn skip_id() -> egui::Id {
+    egui::Id::new("confirm_submission_skip")
+}
+
+fn dont_ask_id() -> egui::Id {
+    egui::Id::new("confirm_submission_dont_ask")
+}
+
+/// Test runs go straight through, as does everything once "don't ask again" was ticked.
+/// That lives in temporary memory, so it only lasts for the session.
+pub fn needs_confirmation(ctx: &egui::Context, submission: &Submission) -> bool {
+    !submission.test && !ctx.data(|d| d.get_temp::<bool>(skip_id()).unwrap_or(false))
+}
+
+/// Modal summary of `submission`, drawn over everything else until the user decides.
+pub fn confirm_submission(ctx: &egui::Context, submission: &Submission) -> Choice {
+    let mut choice = Choice::Undecided;
+
+    // Swallow clicks aimed at the windows underneath
+    let screen = ctx.screen_rect();
+    egui::Area::new("confirm_submission_backdrop")
+        .order(egui::Order::Foreground)
+        .fixed_pos(screen.min)
+        .show(ctx, |ui| {
+            ui.painter()
+                .rect_filled(screen, 0.0, egui::Color32::from_black_alpha(120));
+            ui.allocate_rect(screen, egui::Sense::click());
+        });
+
+    let mut dont_ask = ctx.data(|d| d.get_temp::<bool>(dont_ask_id()).unwrap_or(false));
+    egui::Area::new("confirm_submission")
+        .order(egui::Order::Tooltip)
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .show(ctx, |ui| {
+            egui::Frame::window(ui.style()).show(ui, |ui| {
+                ui.heading("Submit for score?");
+                ui.label("This will be scored and posted to the leaderboard.");
+                ui.separator();
+                egui::Grid::new("confirm_submission_summary")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label("Challenge:");
+                        ui.strong(submission.challenge.as_deref().unwrap_or("None"));
+                        ui.end_row();
+                        ui.label("Language:");
+                        ui.strong(submission.language.to_string());
+                        ui.end_row();
+                        ui.label("Filename:");
+                        ui.strong(&submission.filename);
+                        ui.end_row();
+                        if !submission.files.is_empty() {
+                            let names: Vec<&str> = submission
+                                .files
+                                .iter()
+                                .map(|f| f.filename.as_str())
+                                .collect();
+                            ui.label("Extra files:");
+                            ui.strong(names.join(", "));
+                            ui.end_row();
+                        }
+                        ui.label("Mode:");
+                        ui.strong(match submission.test {
+                            true => "🧪 Test run",
+                            false => "🏆 Scored",
+                        });
+                        ui.end_row();
+                    });
+                ui.separator();
+                ui.checkbox(&mut dont_ask, "Don't ask again this session");
+                ui.horizontal(|ui| {
+                    if ui.button("🏆 Submit").clicked() {
+                        choice = Choice::Submit;
+                    }
+                    if ui.button("Cancel").clicked()
+                        || ui.input(|i| i.key_pressed(egui::Key::Escape))
+                    {
+                        choice = Choice::Cancel;
+                    }
+                });
+            });
+        });
+
+    ctx.data_mut(|d| {
+        d.insert_temp(dont_ask_id(), dont_ask);
+        if choice == Choice::Submit && dont_ask {
+            d.insert_temp(skip_id(), true);
+        }
+    });
+    choice
+}
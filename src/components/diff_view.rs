@@ -0,0 +1,49 @@
+use similar::{ChangeTag, TextDiff};
+
+/// The lines of `new` compared against `old`, tagged with whether they were added,
+/// removed or left alone. Line endings are dropped.
+pub fn diff_lines(old: &str, new: &str) -> Vec<(ChangeTag, String)> {
+    TextDiff::from_lines(old, new)
+        .iter_all_changes()
+        .map(|change| {
+            let line = change.value().trim_end_matches(['\r', '\n']).to_string();
+            (change.tag(), line)
+        })
+        .collect()
+}
+
+/// Unified diff of two pieces of code, removed lines in red and added ones in green.
+pub fn diff_view(ui: &mut egui::Ui, old: &str, new: &str) {
+    let font = egui::TextStyle::Monospace.resolve(ui.style());
+    for (tag, line) in diff_lines(old, new) {
+        let (sign, color) = match tag {
+            ChangeTag::Delete => ("-", ui.visuals().error_fg_color),
+            ChangeTag::Insert => ("+", egui::Color32::from_rgb(0x3f, 0xb9, 0x50)),
+            ChangeTag::Equal => (" ", ui.visuals().weak_text_color()),
+        };
+        ui.label(
+            egui::RichText::new(format!("{} {}", sign, line))
+                .font(font.clone())
+                .color(color),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_lines() {
+        let diff = diff_lines("a\nb\nc\n", "a\nc\nd\n");
+        assert_eq!(
+            diff,
+            vec![
+                (ChangeTag::Equal, "a".to_string()),
+                (ChangeTag::Delete, "b".to_string()),
+                (ChangeTag::Equal, "c".to_string()),
+                (ChangeTag::Insert, "d".to_string()),
+            ]
+        );
+    }
+}
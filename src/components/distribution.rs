@@ -0,0 +1,127 @@
+use scoreboard_db::NiceTime;
+
+const BUCKETS: usize = 30;
+const HEIGHT: f32 = 40.0;
+
+/// Times counted into buckets of equal width on a log scale, since the
+/// slowest entries are often orders of magnitude behind the fastest.
+#[derive(Debug, PartialEq)]
+pub struct Histogram {
+    pub counts: Vec<usize>,
+    /// log10 of the fastest and slowest time
+    lo: f64,
+    hi: f64,
+}
+
+impl Histogram {
+    pub fn new(times_ns: &[f64], buckets: usize) -> Option<Self> {
+        let logs: Vec<f64> = times_ns.iter().map(|t| t.max(1.0).log10()).collect();
+        let lo = logs.iter().copied().reduce(f64::min)?;
+        let hi = logs.iter().copied().reduce(f64::max)?;
+        let mut histogram = Self {
+            counts: vec![0; buckets.max(1)],
+            lo,
+            hi,
+        };
+        for time in times_ns {
+            let bucket = histogram.bucket(*time);
+            histogram.counts[bucket] += 1;
+        }
+        Some(histogram)
+    }
+
+    pub fn bucket(&self, time_ns: f64) -> usize {
+        let span = self.hi - self.lo;
+        if span <= f64::EPSILON {
+            return 0;
+        }
+        let position = (time_ns.max(1.0).log10() - self.lo) / span;
+        ((position * self.counts.len() as f64) as usize).min(self.counts.len() - 1)
+    }
+
+    /// Fastest and slowest time that would land in `bucket`.
+    pub fn bounds(&self, bucket: usize) -> (f64, f64) {
+        let width = (self.hi - self.lo) / self.counts.len() as f64;
+        let at = |i: usize| 10f64.powf(self.lo + width * i as f64);
+        (at(bucket), at(bucket + 1))
+    }
+}
+
+/// A small bar chart of `times_ns`, with the bucket holding `mine` picked out.
+pub fn distribution_ui(ui: &mut egui::Ui, times_ns: &[f64], mine: Option<f64>) {
+    let Some(histogram) = Histogram::new(times_ns, BUCKETS) else {
+        return;
+    };
+    let (rect, response) = ui.allocate_exact_size(
+        egui::vec2(ui.available_width(), HEIGHT),
+        egui::Sense::hover(),
+    );
+    let tallest = histogram.counts.iter().copied().max().unwrap_or(1).max(1) as f32;
+    let bar_width = rect.width() / histogram.counts.len() as f32;
+    let mine = mine.map(|t| histogram.bucket(t));
+    let hovered = response
+        .hover_pos()
+        .map(|pos| (((pos.x - rect.left()) / bar_width) as usize).min(BUCKETS - 1));
+
+    let visuals = ui.visuals();
+    let painter = ui.painter_at(rect);
+    painter.line_segment(
+        [rect.left_bottom(), rect.right_bottom()],
+        visuals.widgets.noninteractive.bg_stroke,
+    );
+    for (i, count) in histogram.counts.iter().enumerate() {
+        if *count == 0 {
+            continue;
+        }
+        let height = (*count as f32 / tallest * rect.height()).max(1.0);
+        let left = rect.left() + i as f32 * bar_width;
+        let bar = egui::Rect::from_min_max(
+            egui::pos2(left + 0.5, rect.bottom() - height),
+            egui::pos2(left + bar_width - 0.5, rect.bottom()),
+        );
+        let color = if Some(i) == mine {
+            visuals.selection.bg_fill
+        } else if Some(i) == hovered {
+            visuals.strong_text_color()
+        } else {
+            visuals.weak_text_color()
+        };
+        painter.rect_filled(bar, 0.0, color);
+    }
+
+    if let Some(i) = hovered {
+        let (from, to) = histogram.bounds(i);
+        let count = histogram.counts[i];
+        response.on_hover_text(format!(
+            "{} – {}: {} score{}",
+            NiceTime::new(from),
+            NiceTime::new(to),
+            count,
+            if count == 1 { "" } else { "s" }
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram() {
+        assert_eq!(Histogram::new(&[], 10), None);
+
+        let same = Histogram::new(&[500.0, 500.0], 10).unwrap();
+        assert_eq!(same.counts[0], 2);
+        assert_eq!(same.counts.iter().sum::<usize>(), 2);
+
+        // 10ns to 10s, log scale puts one decade in each bucket
+        let times = [10.0, 50.0, 2_000.0, 3_000.0, 10_000_000_000.0];
+        let histogram = Histogram::new(&times, 9).unwrap();
+        assert_eq!(histogram.counts, vec![2, 0, 2, 0, 0, 0, 0, 0, 1]);
+        assert_eq!(histogram.bucket(10.0), 0);
+        assert_eq!(histogram.bucket(10_000_000_000.0), 8);
+        let (from, to) = histogram.bounds(2);
+        assert!((from - 1_000.0).abs() < 1e-6);
+        assert!((to - 10_000.0).abs() < 1e-6);
+    }
+}
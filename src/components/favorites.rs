@@ -0,0 +1,141 @@
+use super::challenge_badge;
+use crate::helpers::ChallengeCollection;
+use std::collections::HashSet;
+
+fn favorites_id() -> egui::Id {
+    egui::Id::new("favorite_challenges")
+}
+
+/// Commands of the challenges the user starred, kept in egui's persisted memory.
+pub fn favorites(ctx: &egui::Context) -> HashSet<String> {
+    ctx.data_mut(|d| d.get_persisted(favorites_id()))
+        .unwrap_or_default()
+}
+
+fn set_favorite(ctx: &egui::Context, command: &str, favorite: bool) {
+    ctx.data_mut(|d| {
+        let favorites = d.get_persisted_mut_or_default::<HashSet<String>>(favorites_id());
+        match favorite {
+            true => favorites.insert(command.to_string()),
+            false => favorites.remove(command),
+        };
+    });
+}
+
+/// What a group of challenges in a selector is headed by.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Group {
+    /// The starred ones, whatever category they're in
+    Favorites,
+    Category(Option<String>),
+}
+
+/// `commands` the way a selector lists them: the starred ones up top, then the rest
+/// by category as [`ChallengeCollection::grouped`] has them. Order is kept within each.
+pub fn grouped(
+    challenges: &ChallengeCollection,
+    commands: Vec<String>,
+    favorites: &HashSet<String>,
+) -> Vec<(Group, Vec<String>)> {
+    let (pinned, rest): (Vec<String>, Vec<String>) =
+        commands.into_iter().partition(|c| favorites.contains(c));
+    let mut groups = Vec::new();
+    if !pinned.is_empty() {
+        groups.push((Group::Favorites, pinned));
+    }
+    groups.extend(
+        challenges
+            .grouped(rest)
+            .into_iter()
+            .map(|(category, commands)| (Group::Category(category), commands)),
+    );
+    groups
+}
+
+/// [`challenge_badge::category_heading`], or one for the favourites.
+pub fn group_heading(ui: &mut egui::Ui, group: &Group, only_group: bool) {
+    match group {
+        Group::Favorites => {
+            ui.label(egui::RichText::new("★ Favourites").small().weak());
+        }
+        Group::Category(category) => {
+            challenge_badge::category_heading(ui, category.as_deref(), only_group)
+        }
+    }
+}
+
+/// ★ for a favourite and ☆ otherwise, clicking it flips which.
+pub fn star_button(ui: &mut egui::Ui, command: &str, favorites: &HashSet<String>) {
+    let favorite = favorites.contains(command);
+    let (star, hint) = match favorite {
+        true => ("★", "Unpin from the top"),
+        false => ("☆", "Pin to the top"),
+    };
+    let button = egui::Button::new(star).frame(false).small();
+    if ui.add(button).on_hover_text(hint).clicked() {
+        set_favorite(ui.ctx(), command, !favorite);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(items: &[&str]) -> Vec<String> {
+        items.iter().map(|c| c.to_string()).collect()
+    }
+
+    #[test]
+    fn test_favorites_then_categories() {
+        let challenges = ChallengeCollection {
+            items: serde_json::from_str(
+                r#"[
+                {"command": "2331", "name": "a", "table": "t", "doc": "", "category": "Strings"},
+                {"command": "2332", "name": "b", "table": "t", "doc": "", "category": "Arrays"},
+                {"command": "2333", "name": "c", "table": "t", "doc": ""},
+                {"command": "2334", "name": "d", "table": "t", "doc": "", "category": "Strings"}
+            ]"#,
+            )
+            .unwrap(),
+        };
+        let commands = challenges.commands();
+
+        let favorites = HashSet::from(["2334".to_string(), "2332".to_string()]);
+        assert_eq!(
+            grouped(&challenges, commands.clone(), &favorites),
+            vec![
+                (Group::Favorites, strings(&["2332", "2334"])),
+                (
+                    Group::Category(Some("Strings".to_string())),
+                    strings(&["2331"])
+                ),
+                (Group::Category(None), strings(&["2333"])),
+            ]
+        );
+
+        assert_eq!(
+            grouped(&challenges, commands, &HashSet::new()),
+            vec![
+                (
+                    Group::Category(Some("Arrays".to_string())),
+                    strings(&["2332"])
+                ),
+                (
+                    Group::Category(Some("Strings".to_string())),
+                    strings(&["2331", "2334"])
+                ),
+                (Group::Category(None), strings(&["2333"])),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_favorites_are_remembered() {
+        let ctx = egui::Context::default();
+        assert!(favorites(&ctx).is_empty());
+        set_favorite(&ctx, "2331", true);
+        set_favorite(&ctx, "2332", true);
+        set_favorite(&ctx, "2331", false);
+        assert_eq!(favorites(&ctx), HashSet::from(["2332".to_string()]));
+    }
+}
@@ -0,0 +1,51 @@
+use crate::helpers::Languages;
+use egui::Color32;
+
+/// The accent colour for a language, the same everywhere it's shown.
+/// Mid-tones, so they stand out against both the light and the dark theme.
+pub fn language_color(language: Languages) -> Color32 {
+    match language {
+        Languages::C => Color32::from_rgb(0x6e, 0x7b, 0x8b),
+        Languages::Cpp => Color32::from_rgb(0xf3, 0x4b, 0x7d),
+        Languages::CSharp => Color32::from_rgb(0x17, 0x86, 0x00),
+        Languages::Go => Color32::from_rgb(0x00, 0xad, 0xd8),
+        Languages::Java => Color32::from_rgb(0xb0, 0x72, 0x19),
+        Languages::JavaScript => Color32::from_rgb(0xe0, 0xc0, 0x30),
+        Languages::Python => Color32::from_rgb(0x35, 0x72, 0xa5),
+        Languages::Rust => Color32::from_rgb(0xde, 0x84, 0x4a),
+        Languages::ShellScript => Color32::from_rgb(0x6c, 0xb0, 0x3c),
+    }
+}
+
+/// A language name with its coloured dot in front.
+/// Names we don't recognise are shown without a dot.
+pub fn language_label(ui: &mut egui::Ui, name: &str) -> egui::Response {
+    ui.horizontal(|ui| {
+        if let Some(language) = Languages::from_name(name) {
+            let size = ui.text_style_height(&egui::TextStyle::Body) * 0.6;
+            let (rect, _) = ui.allocate_exact_size(egui::vec2(size, size), egui::Sense::hover());
+            // A thin outline keeps the lighter colours visible on a light background
+            ui.painter().circle(
+                rect.center(),
+                size / 2.0,
+                language_color(language),
+                egui::Stroke::new(1.0, ui.visuals().weak_text_color()),
+            );
+        }
+        ui.label(name);
+    })
+    .response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_colors_are_distinct() {
+        let colors: Vec<Color32> = Languages::iter().map(language_color).collect();
+        for (i, color) in colors.iter().enumerate() {
+            assert!(!colors[i + 1..].contains(color));
+        }
+    }
+}
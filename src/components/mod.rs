@@ -1 +1,12 @@
+pub mod challenge_badge;
+pub mod code_input;
+pub mod code_view;
+pub mod confirm_submission;
+pub mod diff_view;
+pub mod distribution;
+pub mod favorites;
+pub mod language_badge;
+pub mod notifications;
+pub mod output_panel;
 pub mod password;
+pub mod refresh_button;
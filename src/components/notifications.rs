@@ -0,0 +1,70 @@
+use crate::helpers::submission::SubmissionResult;
+use egui_notify::Toasts;
+use std::time::Duration;
+
+/// How long a notification stays up before it dismisses itself.
+const DURATION: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Level {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+#[derive(Clone)]
+struct Notification {
+    level: Level,
+    text: String,
+}
+
+fn queue_id() -> egui::Id {
+    egui::Id::new("notifications")
+}
+
+/// Queues a toast from anywhere that has the context.
+/// It is drawn by [`show`], which the top-level app calls once per frame.
+pub fn notify(ctx: &egui::Context, level: Level, text: impl Into<String>) {
+    let notification = Notification {
+        level,
+        text: text.into(),
+    };
+    ctx.data_mut(|d| {
+        d.get_temp_mut_or_default::<Vec<Notification>>(queue_id())
+            .push(notification)
+    });
+    ctx.request_repaint();
+}
+
+/// Toasts for the end of a submission; nothing while it's still busy.
+pub fn notify_submission(ctx: &egui::Context, result: &SubmissionResult) {
+    match result {
+        SubmissionResult::Success { message, .. } => notify(ctx, Level::Success, message),
+        SubmissionResult::Failure {
+            reason, message, ..
+        } => notify(
+            ctx,
+            Level::Error,
+            format!("{} {}: {}", reason.icon(), reason, message),
+        ),
+        SubmissionResult::NotAuthorized => notify(ctx, Level::Warning, result.to_string()),
+        SubmissionResult::NotStarted | SubmissionResult::Busy => {}
+    }
+}
+
+/// Moves everything queued by [`notify`] into `toasts` and draws them stacked in the corner.
+pub fn show(ctx: &egui::Context, toasts: &mut Toasts) {
+    let queued = ctx
+        .data_mut(|d| std::mem::take(d.get_temp_mut_or_default::<Vec<Notification>>(queue_id())));
+    for notification in queued {
+        let toast = match notification.level {
+            Level::Info => toasts.info(notification.text),
+            Level::Success => toasts.success(notification.text),
+            Level::Warning => toasts.warning(notification.text),
+            Level::Error => toasts.error(notification.text),
+        };
+        toast.set_duration(Some(DURATION));
+    }
+    toasts.show(ctx);
+}
@@ -0,0 +1,173 @@
+use crate::helpers::submission::RunOutput;
+use egui::text::{LayoutJob, TextFormat};
+use egui::Color32;
+
+const MAX_HEIGHT: f32 = 200.0;
+
+/// The SGR attributes we keep, anything else is dropped.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct AnsiStyle {
+    /// One of the 16 standard colours, the bright ones being 8 to 15
+    color: Option<u8>,
+    bold: bool,
+}
+
+impl AnsiStyle {
+    fn apply(&mut self, params: &str) {
+        if params.is_empty() {
+            *self = AnsiStyle::default();
+            return;
+        }
+        for code in params.split(';') {
+            match code.parse::<u8>() {
+                Ok(0) => *self = AnsiStyle::default(),
+                Ok(1) => self.bold = true,
+                Ok(22) => self.bold = false,
+                Ok(code @ 30..=37) => self.color = Some(code - 30),
+                Ok(39) => self.color = None,
+                Ok(code @ 90..=97) => self.color = Some(code - 90 + 8),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// `text` split up wherever its style changes, with every escape sequence
+/// taken out, including the ones we don't draw.
+fn parse_ansi(text: &str) -> Vec<(String, AnsiStyle)> {
+    let mut spans = Vec::new();
+    let mut style = AnsiStyle::default();
+    let mut current = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\u{1b}' {
+            current.push(c);
+            continue;
+        }
+        if chars.peek() != Some(&'[') {
+            continue;
+        }
+        chars.next();
+        let mut params = String::new();
+        let mut command = None;
+        for c in chars.by_ref() {
+            if ('@'..='~').contains(&c) {
+                command = Some(c);
+                break;
+            }
+            params.push(c);
+        }
+        if command != Some('m') {
+            continue;
+        }
+        let mut next = style;
+        next.apply(&params);
+        if next != style && !current.is_empty() {
+            spans.push((std::mem::take(&mut current), style));
+        }
+        style = next;
+    }
+    if !current.is_empty() {
+        spans.push((current, style));
+    }
+    spans
+}
+
+/// Black and white are left to the theme, so they can't vanish into the background.
+fn ansi_color(index: u8, visuals: &egui::Visuals) -> Color32 {
+    match index % 8 {
+        0 => visuals.weak_text_color(),
+        1 => Color32::from_rgb(0xcd, 0x31, 0x31),
+        2 => Color32::from_rgb(0x0d, 0xbc, 0x79),
+        3 => Color32::from_rgb(0xc8, 0xa0, 0x10),
+        4 => Color32::from_rgb(0x24, 0x72, 0xc8),
+        5 => Color32::from_rgb(0xbc, 0x3f, 0xbc),
+        6 => Color32::from_rgb(0x11, 0xa8, 0xcd),
+        _ => visuals.text_color(),
+    }
+}
+
+fn ansi_job(ui: &egui::Ui, text: &str) -> LayoutJob {
+    let font_id = egui::TextStyle::Monospace.resolve(ui.style());
+    let visuals = ui.visuals();
+    let mut job = LayoutJob::default();
+    for (span, style) in parse_ansi(text) {
+        // The monospace font has no bold, so make it stand out instead
+        let color = match (style.color, style.bold) {
+            (Some(index), _) => ansi_color(index, visuals),
+            (None, true) => visuals.strong_text_color(),
+            (None, false) => visuals.text_color(),
+        };
+        job.append(
+            &span,
+            0.0,
+            TextFormat {
+                font_id: font_id.clone(),
+                color,
+                ..Default::default()
+            },
+        );
+    }
+    job
+}
+
+/// A scrolling box for each of the compiler output, stdout and stderr that has
+/// something in it, keeping any colours the backend passed along.
+pub fn output_panel(ui: &mut egui::Ui, output: &RunOutput) {
+    for (title, text) in output.sections() {
+        egui::CollapsingHeader::new(title)
+            .default_open(true)
+            .show(ui, |ui| {
+                egui::ScrollArea::both()
+                    .id_source(title)
+                    .max_height(MAX_HEIGHT)
+                    .stick_to_bottom(true)
+                    .show(ui, |ui| {
+                        let job = ansi_job(ui, text);
+                        ui.add(egui::Label::new(job).wrap(false));
+                    });
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_text() {
+        assert_eq!(
+            parse_ansi("no colour\n"),
+            vec![("no colour\n".to_string(), AnsiStyle::default())]
+        );
+        assert!(parse_ansi("").is_empty());
+    }
+
+    #[test]
+    fn test_colours() {
+        let red = AnsiStyle {
+            color: Some(1),
+            bold: false,
+        };
+        let bright_bold = AnsiStyle {
+            color: Some(10),
+            bold: true,
+        };
+        assert_eq!(
+            parse_ansi("\u{1b}[31merror\u{1b}[0m: \u{1b}[1;92mok\u{1b}[m"),
+            vec![
+                ("error".to_string(), red),
+                (": ".to_string(), AnsiStyle::default()),
+                ("ok".to_string(), bright_bold),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_other_sequences_are_stripped() {
+        // 256 colours, cursor movement and a stray escape
+        let spans = parse_ansi("\u{1b}[38;5;196ma\u{1b}[2Kb\u{1b}c");
+        let text: String = spans.iter().map(|(s, _)| s.as_str()).collect();
+        assert_eq!(text, "abc");
+    }
+}
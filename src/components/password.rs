@@ -1,5 +1,15 @@
+/// Shows or hides the password while its field has focus.
+pub const SHORTCUT_SHOW_PASSWORD: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::ALT, egui::Key::P);
+
 #[allow(clippy::ptr_arg)] // false positive
 pub fn _password_ui(ui: &mut egui::Ui, password: &mut String) -> egui::Response {
+    password_field_ui(ui, password, "Password")
+}
+
+/// `label` is what screen readers announce for the field, and its hint while empty.
+#[allow(clippy::ptr_arg)] // false positive
+fn password_field_ui(ui: &mut egui::Ui, password: &mut String, label: &str) -> egui::Response {
     // Generate an id for the state
     let state_id = ui.id().with("show_plaintext");
 
@@ -7,33 +17,115 @@ pub fn _password_ui(ui: &mut egui::Ui, password: &mut String) -> egui::Response
     // You should get state by value, not by reference to avoid borrowing of [`Memory`].
     let mut show_plaintext = ui.data_mut(|d| d.get_temp::<bool>(state_id).unwrap_or(false));
 
+    // Browsers don't tell us about Caps Lock, so guess it from the typed letters instead.
+    // Until a letter has been typed the warning simply stays hidden.
+    let text_id = ui.id().with("password_text");
+    let caps_lock_id = ui.id().with("caps_lock");
+    let focused = ui.memory(|m| m.has_focus(text_id));
+    let mut caps_lock = ui.data_mut(|d| d.get_temp::<bool>(caps_lock_id).unwrap_or(false));
+    if focused {
+        let toggled = ui.input_mut(|i| {
+            let toggled = i.consume_shortcut(&SHORTCUT_SHOW_PASSWORD);
+            // Option+P types a "π" on a Mac, which shouldn't end up in the password
+            if toggled {
+                i.events.retain(|e| !matches!(e, egui::Event::Text(_)));
+            }
+            toggled
+        });
+        if toggled {
+            show_plaintext = !show_plaintext;
+        }
+        ui.input(|i| {
+            for event in &i.events {
+                if let egui::Event::Text(text) = event {
+                    if let Some(on) = caps_lock_from_text(text, i.modifiers.shift) {
+                        caps_lock = on;
+                    }
+                }
+            }
+        });
+    }
+
     // Process ui, change a local copy of the state
     // We want TextEdit to fill entire space, and have button after that, so in that case we can
     // change direction to right_to_left.
     let result = ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+        // Tab follows the order widgets are added in, so only keep the toggle's place here
+        // and add it after the field, letting Tab go from the field to the toggle.
+        let toggle_text: egui::WidgetText = "👁".into();
+        let padding = ui.spacing().button_padding;
+        let toggle_size = toggle_text
+            .into_galley(ui, Some(false), f32::INFINITY, egui::TextStyle::Button)
+            .size()
+            + 2.0 * padding;
+        let toggle_size = toggle_size.max(egui::vec2(0.0, ui.spacing().interact_size.y));
+        let (toggle_rect, _) = ui.allocate_exact_size(toggle_size, egui::Sense::hover());
+
+        if focused && caps_lock {
+            ui.colored_label(ui.visuals().warn_fg_color, "⚠ Caps Lock is on");
+        }
+        // Show the password field:
+        let field = ui.add_sized(
+            ui.available_size(),
+            egui::TextEdit::singleline(password)
+                .id(text_id)
+                .hint_text(label)
+                .password(!show_plaintext),
+        );
+        let masked = match show_plaintext {
+            true => password.clone(),
+            false => "•".repeat(password.chars().count()),
+        };
+        field.widget_info(|| {
+            let mut info = egui::WidgetInfo::text_edit(&masked, &masked);
+            info.label = Some(label.to_string());
+            info
+        });
+
         // Toggle the `show_plaintext` bool with a button:
+        let toggle_label = match show_plaintext {
+            true => "Hide password",
+            false => "Show password",
+        };
         let response = ui
-            .add(egui::SelectableLabel::new(show_plaintext, "👁"))
-            .on_hover_text("Show/hide password");
+            .put(toggle_rect, egui::SelectableLabel::new(show_plaintext, "👁"))
+            .on_hover_text(format!(
+                "Show/hide password ({})",
+                ui.ctx().format_shortcut(&SHORTCUT_SHOW_PASSWORD)
+            ));
+        response.widget_info(|| {
+            egui::WidgetInfo::selected(egui::WidgetType::Button, show_plaintext, toggle_label)
+        });
 
         if response.clicked() {
             show_plaintext = !show_plaintext;
         }
-        // Show the password field:
-        ui.add_sized(
-            ui.available_size(),
-            egui::TextEdit::singleline(password).password(!show_plaintext),
-        );
     });
 
     // Store the (possibly changed) state:
-    ui.data_mut(|d| d.insert_temp(state_id, show_plaintext));
+    ui.data_mut(|d| {
+        d.insert_temp(state_id, show_plaintext);
+        d.insert_temp(caps_lock_id, caps_lock);
+    });
 
     // All done! Return the interaction response so the user can check what happened
     // (hovered, clicked, …) and maybe show a tooltip:
     result.response
 }
 
+/// A letter typed in the opposite case to what Shift implies means Caps Lock is on.
+/// Returns `None` when the text says nothing either way (digits, symbols, ...).
+fn caps_lock_from_text(text: &str, shift: bool) -> Option<bool> {
+    let c = text.chars().last().filter(|c| c.is_alphabetic())?;
+    if c.is_uppercase() {
+        Some(!shift)
+    } else if c.is_lowercase() {
+        Some(shift)
+    } else {
+        None
+    }
+}
+
 // A wrapper that allows the more idiomatic usage pattern: `ui.add(…)`
 /// Password entry field with ability to toggle character hiding.
 ///
@@ -44,3 +136,184 @@ pub fn _password_ui(ui: &mut egui::Ui, password: &mut String) -> egui::Response
 pub fn _password(password: &mut String) -> impl egui::Widget + '_ {
     move |ui: &mut egui::Ui| _password_ui(ui, password)
 }
+
+/// Two password fields which have to match, e.g. for registration.
+/// The first one rates the password as it's typed.
+///
+/// Returns the response of both fields together and whether the passwords match.
+pub fn password_confirm_ui(
+    ui: &mut egui::Ui,
+    password: &mut String,
+    confirm: &mut String,
+) -> (egui::Response, bool) {
+    let result = ui.vertical(|ui| {
+        // Separate ids keep the show/hide toggles apart
+        ui.push_id("password", |ui| password_with_strength_ui(ui, password));
+        let confirm_response = ui
+            .push_id("confirm", |ui| {
+                password_field_ui(ui, confirm, "Confirm password")
+            })
+            .inner;
+
+        let matches = password == confirm;
+        if !matches && !confirm.is_empty() {
+            let color = ui.visuals().error_fg_color;
+            ui.painter().rect_stroke(
+                confirm_response.rect.expand(2.0),
+                2.0,
+                egui::Stroke::new(1.0, color),
+            );
+            ui.colored_label(color, "Passwords do not match");
+        }
+        matches
+    });
+
+    (result.response, result.inner)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Strength {
+    Weak,
+    Fair,
+    Strong,
+}
+
+impl Strength {
+    fn fraction(&self) -> f32 {
+        match self {
+            Strength::Weak => 1.0 / 3.0,
+            Strength::Fair => 2.0 / 3.0,
+            Strength::Strong => 1.0,
+        }
+    }
+
+    fn color(&self) -> egui::Color32 {
+        match self {
+            Strength::Weak => egui::Color32::from_rgb(200, 60, 60),
+            Strength::Fair => egui::Color32::from_rgb(220, 160, 40),
+            Strength::Strong => egui::Color32::from_rgb(60, 170, 80),
+        }
+    }
+}
+
+impl std::fmt::Display for Strength {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Strength::Weak => write!(f, "Weak"),
+            Strength::Fair => write!(f, "Fair"),
+            Strength::Strong => write!(f, "Strong"),
+        }
+    }
+}
+
+const COMMON_PASSWORDS: [&str; 12] = [
+    "123456",
+    "12345678",
+    "123456789",
+    "abc123",
+    "admin",
+    "iloveyou",
+    "letmein",
+    "password",
+    "password1",
+    "password123",
+    "qwerty",
+    "welcome",
+];
+
+/// Rates a password on its length and how many kinds of characters it uses.
+pub fn password_strength(password: &str) -> Strength {
+    let length = password.chars().count();
+    if length < 8 || COMMON_PASSWORDS.contains(&password.to_lowercase().as_str()) {
+        return Strength::Weak;
+    }
+
+    let classes = [
+        password.chars().any(|c| c.is_lowercase()),
+        password.chars().any(|c| c.is_uppercase()),
+        password.chars().any(|c| c.is_ascii_digit()),
+        password.chars().any(|c| !c.is_alphanumeric()),
+    ]
+    .iter()
+    .filter(|c| **c)
+    .count();
+
+    let points = [length >= 12, classes >= 3, classes == 4]
+        .iter()
+        .filter(|p| **p)
+        .count();
+    match points {
+        0 => Strength::Weak,
+        1 => Strength::Fair,
+        _ => Strength::Strong,
+    }
+}
+
+pub fn password_with_strength_ui(ui: &mut egui::Ui, password: &mut String) -> egui::Response {
+    ui.vertical(|ui| {
+        _password_ui(ui, password);
+        if !password.is_empty() {
+            let strength = password_strength(password);
+            ui.horizontal(|ui| {
+                ui.add(
+                    egui::ProgressBar::new(strength.fraction())
+                        .desired_width(100.0)
+                        .desired_height(6.0)
+                        .fill(strength.color()),
+                );
+                ui.colored_label(strength.color(), strength.to_string());
+            });
+        }
+    })
+    .response
+}
+
+/// A password with its confirmation, see [`password_confirm_ui`].
+///
+/// ## Example:
+/// ``` ignore
+/// ui.add(password_confirm(&mut my_password, &mut my_confirmation));
+/// ```
+pub fn password_confirm<'a>(
+    password: &'a mut String,
+    confirm: &'a mut String,
+) -> impl egui::Widget + 'a {
+    move |ui: &mut egui::Ui| password_confirm_ui(ui, password, confirm).0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_caps_lock_detection() {
+        assert_eq!(caps_lock_from_text("A", false), Some(true));
+        assert_eq!(caps_lock_from_text("a", true), Some(true));
+        assert_eq!(caps_lock_from_text("a", false), Some(false));
+        assert_eq!(caps_lock_from_text("A", true), Some(false));
+        assert_eq!(caps_lock_from_text("1", false), None);
+    }
+
+    #[test]
+    fn test_weak_passwords() {
+        assert_eq!(password_strength(""), Strength::Weak);
+        assert_eq!(password_strength("Ab1!"), Strength::Weak);
+        assert_eq!(password_strength("Password123"), Strength::Weak);
+        assert_eq!(password_strength("abcdefgh"), Strength::Weak);
+    }
+
+    #[test]
+    fn test_fair_passwords() {
+        assert_eq!(password_strength("Abcdefg1"), Strength::Fair);
+        assert_eq!(password_strength("correcthorsebattery"), Strength::Fair);
+    }
+
+    #[test]
+    fn test_strong_passwords() {
+        assert_eq!(password_strength("Abcdefg1!"), Strength::Strong);
+        assert_eq!(
+            password_strength("Correct-Horse-Battery-9"),
+            Strength::Strong
+        );
+    }
+}
@@ -0,0 +1,19 @@
+/// A "Refresh" button that turns into a spinner, and can't be clicked, while `busy`.
+/// It keeps its size either way so the widgets around it don't jump.
+pub fn refresh_button(ui: &mut egui::Ui, busy: bool, enabled: bool) -> egui::Response {
+    let text = egui::WidgetText::from("Refresh").into_galley(
+        ui,
+        Some(false),
+        f32::INFINITY,
+        egui::TextStyle::Button,
+    );
+    let size = text.size() + 2.0 * ui.spacing().button_padding;
+    let label = if busy { "" } else { "Refresh" };
+    let response = ui.add_enabled(enabled && !busy, egui::Button::new(label).min_size(size));
+    if busy {
+        let spinner =
+            egui::Rect::from_center_size(response.rect.center(), egui::Vec2::splat(size.y));
+        egui::Spinner::new().paint_at(ui, spinner);
+    }
+    response
+}
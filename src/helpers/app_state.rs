@@ -1,6 +1,58 @@
-use crate::helpers::ChallengeCollection;
+use crate::helpers::{format::Formatting, submission::Submission, ChallengeCollection, Languages};
 use std::sync::{Arc, Mutex};
 
+/// Baked in at build time, and can be overridden in the settings.
+pub const DEFAULT_BACKEND_URL: &str = match option_env!("BACKEND_URL") {
+    Some(url) => url,
+    None => "http://123.4.5.6:3000/",
+};
+
+/// Checks a backend address typed in by the user, adding the trailing slash
+/// the endpoints are appended to if it's missing.
+pub fn normalize_backend_url(input: &str) -> Result<String, String> {
+    let url = input.trim();
+    let host = url
+        .strip_prefix("http://")
+        .or_else(|| url.strip_prefix("https://"))
+        .ok_or("The URL must start with http:// or https://")?;
+    if host.trim_end_matches('/').is_empty() {
+        return Err("The URL needs a host".to_string());
+    }
+    if host.contains(|c: char| c.is_whitespace() || c == '?' || c == '#') {
+        return Err("The URL can't contain spaces, a query or a fragment".to_string());
+    }
+    match url.ends_with('/') {
+        true => Ok(url.to_string()),
+        false => Ok(format!("{}/", url)),
+    }
+}
+
+/// How requests show who's logged in. Backends that hand out a token instead of
+/// setting a cookie are supported by building with `AUTH_MODE=bearer`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AuthMode {
+    Cookie,
+    Bearer,
+}
+
+impl AuthMode {
+    pub fn configured() -> Self {
+        match option_env!("AUTH_MODE") {
+            Some(mode) if mode.eq_ignore_ascii_case("bearer") => AuthMode::Bearer,
+            _ => AuthMode::Cookie,
+        }
+    }
+}
+
+/// What goes with a request that needs a login.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Auth {
+    /// Nothing, e.g. in bearer mode before logging in
+    None,
+    Cookie,
+    Bearer(String),
+}
+
 #[derive(Clone)]
 pub enum LoginState {
     LoggedIn,
@@ -9,24 +61,67 @@ pub enum LoginState {
 
 pub struct AppState {
     pub counter: usize,
+    /// Base address of the backend, ending in a slash
+    pub backend_url: String,
     pub logged_in: LoginState,
     pub challenges: ChallengeCollection,
+    pub challenges_error: Option<String>,
+    /// The challenge list is on its way from the backend
+    pub challenges_fetching: bool,
+    /// The backend has answered with a challenge list at least once, even an empty one
+    pub challenges_loaded: bool,
+    pub languages: Vec<Languages>,
     pub last_refresh: chrono::NaiveTime,
     pub last_activity: chrono::NaiveTime,
     pub activity_timeout: chrono::Duration,
     pub refresh_period: chrono::Duration,
+    pub token_expires: Option<chrono::DateTime<chrono::Utc>>,
+    pub token_refresh_margin: chrono::Duration,
+    /// Set when a refresh was refused while logged in, until the user logs in again
+    pub session_expired: bool,
+    /// A past submission waiting to be loaded into the code editor
+    pub reopen_submission: Option<Submission>,
+    /// What the scoreboard and challenge info start on, nothing is selected if `None`
+    pub default_challenge: Option<String>,
+    /// Bumped every time the backend accepts a submission, apps showing scores or
+    /// submissions refresh when it moves past what they last saw
+    pub submissions_accepted: u64,
+    pub formatting: Formatting,
+    /// What the backend said while it's down for maintenance. Nothing can be
+    /// submitted until it clears, but scores can still be looked at
+    pub maintenance: Option<String>,
+    pub auth_mode: AuthMode,
+    /// Token from the last login or refresh, only kept in bearer mode. Never saved,
+    /// so it can't be read out of local storage; after a reload the refresh cookie
+    /// has to get a new one.
+    pub access_token: Option<String>,
 }
 
 impl Default for AppState {
     fn default() -> Self {
         Self {
             counter: 1,
+            backend_url: DEFAULT_BACKEND_URL.to_string(),
             logged_in: LoginState::LoggedOut,
             challenges: ChallengeCollection::default(),
+            challenges_error: None,
+            challenges_fetching: false,
+            challenges_loaded: false,
+            languages: Languages::iter().collect(),
             last_refresh: chrono::Utc::now().time(),
             last_activity: chrono::Utc::now().time(),
             activity_timeout: chrono::Duration::minutes(10),
             refresh_period: chrono::Duration::minutes(5),
+            token_expires: None,
+            token_refresh_margin: chrono::Duration::seconds(30),
+            session_expired: false,
+            reopen_submission: None,
+            default_challenge: None,
+            submissions_accepted: 0,
+            formatting: Formatting::default(),
+            maintenance: None,
+            auth_mode: AuthMode::configured(),
+            access_token: None,
         }
     }
 }
@@ -36,26 +131,137 @@ impl AppState {
         let app = Arc::clone(app_state);
         let mut app = app.lock().unwrap();
         app.logged_in = LoginState::LoggedIn;
+        app.session_expired = false;
     }
     pub fn set_logged_out(app_state: &Arc<Mutex<AppState>>) {
         let app = Arc::clone(app_state);
         let mut app = app.lock().unwrap();
         app.logged_in = LoginState::LoggedOut;
+        app.token_expires = None;
+        app.access_token = None;
+    }
+
+    /// Logs out once the login lapsed and couldn't be refreshed. Anyone who was logged in
+    /// gets `session_expired`, so they're asked to log in again.
+    pub fn set_session_expired(app_state: &Arc<Mutex<AppState>>) {
+        {
+            let mut app = app_state.lock().unwrap();
+            if let LoginState::LoggedIn = app.logged_in {
+                app.session_expired = true;
+            }
+        }
+        AppState::set_logged_out(app_state);
+    }
+
+    /// Holds on to a token the backend handed out, if requests are sent with one.
+    pub fn set_access_token(app_state: &Arc<Mutex<AppState>>, token: String) {
+        let mut app = app_state.lock().unwrap();
+        if app.auth_mode == AuthMode::Bearer {
+            app.access_token = Some(token);
+        }
     }
 
-    /* Keep refreshing every refresh period
+    pub fn auth(&self) -> Auth {
+        match (self.auth_mode, &self.access_token) {
+            (AuthMode::Cookie, _) => Auth::Cookie,
+            (AuthMode::Bearer, Some(token)) => Auth::Bearer(token.clone()),
+            (AuthMode::Bearer, None) => Auth::None,
+        }
+    }
+
+    /* Remember when a freshly issued token expires,
+    if the backend told us how long it lasts */
+    pub fn set_token_lifetime(
+        app_state: &Arc<Mutex<AppState>>,
+        lifetime: Option<chrono::Duration>,
+    ) {
+        let mut app = app_state.lock().unwrap();
+        app.token_expires = lifetime.map(|lifetime| chrono::Utc::now() + lifetime);
+    }
+
+    /// Address of an endpoint on the current backend, e.g. `api_url("game/submit")`.
+    /// Build request URLs with this so every app talks to the same backend.
+    pub fn api_url(&self, endpoint: &str) -> String {
+        format!("{}api/{}", self.backend_url, endpoint)
+    }
+
+    /* The challenge fetcher stops after a failure
+    and tries again once the error is cleared */
+    pub fn reload_challenges(&mut self) {
+        self.challenges_error = None;
+    }
+
+    /* Keep refreshing every refresh period, or just before
+    the token expires when we know when that is,
     until there's been no activity for longer
     than the activity timeout */
     #[allow(dead_code)]
     pub fn needs_refresh(&self) -> bool {
-        let now = chrono::Utc::now().time();
-        let elapsed_since_activity = now - self.last_activity;
-        let elapsed_since_refresh = now - self.last_refresh;
-        elapsed_since_refresh > self.refresh_period
-            && elapsed_since_activity < self.activity_timeout
+        let now = chrono::Utc::now();
+        let elapsed_since_activity = now.time() - self.last_activity;
+        if elapsed_since_activity >= self.activity_timeout {
+            return false;
+        }
+        match self.token_expires {
+            Some(expires) => now + self.token_refresh_margin >= expires,
+            None => now.time() - self.last_refresh > self.refresh_period,
+        }
     }
     #[allow(dead_code)]
     pub fn update_activity_timer(&mut self) {
         self.last_activity = chrono::Utc::now().time();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_backend_url() {
+        assert_eq!(
+            normalize_backend_url(" https://example.com:3000 "),
+            Ok("https://example.com:3000/".to_string())
+        );
+        assert_eq!(
+            normalize_backend_url("http://10.0.0.1/judge/"),
+            Ok("http://10.0.0.1/judge/".to_string())
+        );
+        assert!(normalize_backend_url("example.com").is_err());
+        assert!(normalize_backend_url("https://").is_err());
+        assert!(normalize_backend_url("https://exa mple.com").is_err());
+        assert!(normalize_backend_url("https://example.com/?x=1").is_err());
+    }
+
+    #[test]
+    fn test_api_url() {
+        let app_state = AppState {
+            backend_url: "http://example.com/".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            app_state.api_url("game/submit"),
+            "http://example.com/api/game/submit"
+        );
+    }
+
+    #[test]
+    fn test_auth() {
+        let app_state = Arc::new(Mutex::new(AppState {
+            auth_mode: AuthMode::Cookie,
+            ..Default::default()
+        }));
+        AppState::set_access_token(&app_state, "abc".to_string());
+        assert_eq!(app_state.lock().unwrap().auth(), Auth::Cookie);
+
+        app_state.lock().unwrap().auth_mode = AuthMode::Bearer;
+        assert_eq!(app_state.lock().unwrap().auth(), Auth::None);
+        AppState::set_access_token(&app_state, "abc".to_string());
+        assert_eq!(
+            app_state.lock().unwrap().auth(),
+            Auth::Bearer("abc".to_string())
+        );
+        AppState::set_logged_out(&app_state);
+        assert_eq!(app_state.lock().unwrap().auth(), Auth::None);
+    }
+}
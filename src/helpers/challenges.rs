@@ -7,12 +7,124 @@ pub struct Challenge {
     pub command: String,
     pub table: String,
     doc: String,
+    /// Optional, older backends don't send these
+    #[serde(default)]
+    pub difficulty: Option<String>,
+    #[serde(default)]
+    pub category: Option<String>,
+}
+
+/// How hard a challenge is, for the badge next to its name.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl Difficulty {
+    /// Anything we don't recognise gets no badge rather than the wrong one.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "easy" | "beginner" => Some(Difficulty::Easy),
+            "medium" | "intermediate" => Some(Difficulty::Medium),
+            "hard" | "advanced" => Some(Difficulty::Hard),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Difficulty::Easy => write!(f, "Easy"),
+            Difficulty::Medium => write!(f, "Medium"),
+            Difficulty::Hard => write!(f, "Hard"),
+        }
+    }
+}
+
+impl Challenge {
+    pub fn difficulty(&self) -> Option<Difficulty> {
+        self.difficulty.as_deref().and_then(Difficulty::from_name)
+    }
+
+    /// Blank categories count as none.
+    pub fn category(&self) -> Option<&str> {
+        self.category
+            .as_deref()
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+    }
+}
+
+/// A challenge whose name or instructions contain the search text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchHit {
+    pub command: String,
+    pub name: String,
+    pub matches: usize,
+    /// Text around the first match in the instructions, split so the match can be highlighted
+    pub snippet: Option<(String, String, String)>,
+}
+
+const SNIPPET_CONTEXT: usize = 30;
+
+/// Byte ranges of every case insensitive, non-overlapping occurrence of `needle`.
+fn find_ignore_case(haystack: &str, needle: &str) -> Vec<(usize, usize)> {
+    let needle: Vec<char> = needle.chars().flat_map(char::to_lowercase).collect();
+    if needle.is_empty() {
+        return Vec::new();
+    }
+    let mut found = Vec::new();
+    let mut skip_until = 0;
+    for (start, _) in haystack.char_indices() {
+        if start < skip_until {
+            continue;
+        }
+        let mut wanted = needle.iter();
+        let mut end = start;
+        let mut lowered = haystack[start..].char_indices().flat_map(|(i, c)| {
+            let next = start + i + c.len_utf8();
+            c.to_lowercase().map(move |l| (l, next))
+        });
+        let matched = wanted.all(|w| match lowered.next() {
+            Some((l, next)) if l == *w => {
+                end = next;
+                true
+            }
+            _ => false,
+        });
+        if matched {
+            found.push((start, end));
+            skip_until = end;
+        }
+    }
+    found
+}
+
+/// Up to `SNIPPET_CONTEXT` chars either side of `start..end`, on one line.
+fn snippet(text: &str, start: usize, end: usize) -> (String, String, String) {
+    let one_line = |s: &str| s.replace(|c: char| c.is_whitespace(), " ");
+    let mut before: Vec<char> = text[..start].chars().rev().take(SNIPPET_CONTEXT).collect();
+    if before.len() < text[..start].chars().count() {
+        before.push('…');
+    }
+    let before: String = before.into_iter().rev().collect();
+    let mut after: String = text[end..].chars().take(SNIPPET_CONTEXT).collect();
+    if after.len() < text[end..].len() {
+        after.push('…');
+    }
+    (
+        one_line(before.trim_start()),
+        one_line(&text[start..end]),
+        one_line(after.trim_end()),
+    )
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ChallengeCollection {
     pub items: Vec<Challenge>,
-    url: String,
 }
 
 impl Default for ChallengeCollection {
@@ -23,23 +135,14 @@ impl Default for ChallengeCollection {
 
 impl ChallengeCollection {
     pub fn new() -> Self {
-        let url = option_env!("BACKEND_URL")
-            .unwrap_or("http://123.4.5.6:3000/")
-            .to_string();
-
-        Self {
-            items: Vec::new(),
-            url,
-        }
+        Self { items: Vec::new() }
     }
 
     #[allow(dead_code)] //inhibit warnings when target =/= WASM
-    pub fn fetch(&mut self, app_state: Arc<Mutex<AppState>>) -> Option<Requestor> {
-        let url = format!("{}api/game/challenge", self.url);
-
+    pub fn fetch(&mut self, url: &str, app_state: Arc<Mutex<AppState>>) -> Option<Requestor> {
         log::debug!("Fetching challenge info");
         let app_state = Arc::clone(&app_state.clone());
-        let mut getter = Requestor::new_get(app_state, &url, true);
+        let mut getter = Requestor::new_get(app_state, url, true).with_retries(3, 250);
         getter.send();
         Some(getter)
     }
@@ -48,10 +151,47 @@ impl ChallengeCollection {
     pub fn from_json(json: &str) -> Self {
         let items: Vec<Challenge> = serde_json::from_str(json).unwrap_or_default();
         log::debug!("Found {} challenges", items.len());
-        Self {
-            items,
-            ..Default::default()
+        Self { items }
+    }
+
+    pub fn commands(&self) -> Vec<String> {
+        self.items.iter().map(|c| c.command.clone()).collect()
+    }
+
+    pub fn get(&self, command: &str) -> Option<&Challenge> {
+        self.items.iter().find(|c| c.command == command)
+    }
+
+    /// Every category some challenge is in, alphabetically.
+    pub fn categories(&self) -> Vec<String> {
+        let mut categories: Vec<String> = self
+            .items
+            .iter()
+            .filter_map(|c| c.category().map(str::to_string))
+            .collect();
+        categories.sort_by_key(|c| c.to_lowercase());
+        categories.dedup();
+        categories
+    }
+
+    /// `commands` split up by category, keeping their order within each. Groups are
+    /// alphabetical with uncategorised challenges last, or just the one group of
+    /// everything when nothing has a category.
+    pub fn grouped(&self, commands: Vec<String>) -> Vec<(Option<String>, Vec<String>)> {
+        let mut groups: Vec<(Option<String>, Vec<String>)> = self
+            .categories()
+            .into_iter()
+            .map(|c| (Some(c), Vec::new()))
+            .collect();
+        groups.push((None, Vec::new()));
+        for command in commands {
+            let category = self.get(&command).and_then(|c| c.category());
+            if let Some(group) = groups.iter_mut().find(|(c, _)| c.as_deref() == category) {
+                group.1.push(command);
+            }
         }
+        groups.retain(|(_, commands)| !commands.is_empty());
+        groups
     }
 
     pub fn get_instructions(&self, challenge: String) -> Option<String> {
@@ -62,6 +202,31 @@ impl ChallengeCollection {
             .map(|c| c.doc.clone())
     }
 
+    /// Challenges mentioning `query`, the most mentions first. A match in the
+    /// name or command counts for more than one in the instructions.
+    pub fn search(&self, query: &str) -> Vec<SearchHit> {
+        let query = query.trim();
+        let mut ranked: Vec<(usize, SearchHit)> = self
+            .items
+            .iter()
+            .filter_map(|c| {
+                let in_doc = find_ignore_case(&c.doc, query);
+                let in_title = find_ignore_case(&c.name, query).len()
+                    + find_ignore_case(&c.command, query).len();
+                let matches = in_doc.len() + in_title;
+                let hit = SearchHit {
+                    command: c.command.clone(),
+                    name: c.name.clone(),
+                    matches,
+                    snippet: in_doc.first().map(|(s, e)| snippet(&c.doc, *s, *e)),
+                };
+                (matches > 0).then_some((in_doc.len() + 10 * in_title, hit))
+            })
+            .collect();
+        ranked.sort_by(|a, b| b.0.cmp(&a.0));
+        ranked.into_iter().map(|(_, hit)| hit).collect()
+    }
+
     pub fn get_table(&self, challenge: String) -> String {
         log::debug!("Getting instructions for {}", challenge);
         match self.items.iter().find(|c| c.command == challenge) {
@@ -111,4 +276,112 @@ mod tests {
 
         assert_eq!(challenges.items[0].name, "Find the odd one out");
     }
+
+    fn challenge(command: &str, name: &str, doc: &str) -> Challenge {
+        Challenge {
+            name: name.to_string(),
+            command: command.to_string(),
+            table: String::new(),
+            doc: doc.to_string(),
+            difficulty: None,
+            category: None,
+        }
+    }
+
+    #[test]
+    fn test_metadata_is_optional() {
+        let json = r#"[
+            {"command": "2331", "name": "a", "table": "t", "doc": "", "difficulty": "Beginner", "category": "Arrays"},
+            {"command": "2332", "name": "b", "table": "t", "doc": "", "difficulty": "impossible", "category": " "},
+            {"command": "2333", "name": "c", "table": "t", "doc": ""}
+        ]"#;
+        let challenges = ChallengeCollection::from_json(json);
+        assert_eq!(challenges.items.len(), 3);
+        assert_eq!(challenges.items[0].difficulty(), Some(Difficulty::Easy));
+        assert_eq!(challenges.items[0].category(), Some("Arrays"));
+        assert_eq!(challenges.items[1].difficulty(), None);
+        assert_eq!(challenges.items[1].category(), None);
+        assert_eq!(challenges.items[2].difficulty(), None);
+        assert_eq!(challenges.categories(), vec!["Arrays"]);
+    }
+
+    #[test]
+    fn test_grouped() {
+        let mut challenges = ChallengeCollection {
+            items: vec![
+                challenge("2331", "a", ""),
+                challenge("2332", "b", ""),
+                challenge("2333", "c", ""),
+                challenge("2334", "d", ""),
+            ],
+        };
+        let commands = vec!["2334", "2333", "2332", "2331"]
+            .into_iter()
+            .map(String::from)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            challenges.grouped(commands.clone()),
+            vec![(None, commands.clone())]
+        );
+
+        challenges.items[0].category = Some("strings".to_string());
+        challenges.items[2].category = Some("Arrays".to_string());
+        challenges.items[3].category = Some("strings".to_string());
+        let groups = challenges.grouped(commands);
+        assert_eq!(
+            groups,
+            vec![
+                (Some("Arrays".to_string()), vec!["2333".to_string()]),
+                (
+                    Some("strings".to_string()),
+                    vec!["2334".to_string(), "2331".to_string()]
+                ),
+                (None, vec!["2332".to_string()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_ignore_case() {
+        assert_eq!(
+            find_ignore_case("Stdin, STDIN, stdout", "stdin"),
+            vec![(0, 5), (7, 12)]
+        );
+        assert_eq!(find_ignore_case("aaaa", "aa"), vec![(0, 2), (2, 4)]);
+        assert_eq!(find_ignore_case("Größe", "ÖSS"), Vec::new());
+        assert_eq!(find_ignore_case("Über", "über"), vec![(0, 5)]);
+        assert_eq!(find_ignore_case("anything", ""), Vec::new());
+    }
+
+    #[test]
+    fn test_search() {
+        let challenges = ChallengeCollection {
+            items: vec![
+                challenge(
+                    "2331",
+                    "Find odds",
+                    "Read a json file.\nThe file holds json arrays",
+                ),
+                challenge("2332", "Json loop", "Listen to stdin"),
+                challenge("2333", "How big?", "Nothing to see"),
+            ],
+        };
+        let hits = challenges.search(" JSON ");
+        assert_eq!(hits.len(), 2);
+        // Named in the title beats a couple of mentions in the text
+        assert_eq!(hits[0].command, "2332");
+        assert_eq!(hits[0].snippet, None);
+        assert_eq!(hits[1].command, "2331");
+        assert_eq!(hits[1].matches, 2);
+        assert_eq!(
+            hits[1].snippet,
+            Some((
+                "Read a ".to_string(),
+                "json".to_string(),
+                " file. The file holds json arr…".to_string()
+            ))
+        );
+        assert!(challenges.search("missing").is_empty());
+        assert!(challenges.search("").is_empty());
+    }
 }
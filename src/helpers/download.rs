@@ -0,0 +1,26 @@
+use wasm_bindgen::JsCast;
+
+/// Hands `contents` to the browser as a file download.
+pub fn download(filename: &str, contents: &str, mime: &str) -> Result<(), String> {
+    let array = js_sys::Array::new();
+    array.push(&wasm_bindgen::JsValue::from_str(contents));
+    let mut options = web_sys::BlobPropertyBag::new();
+    options.type_(mime);
+    let blob = web_sys::Blob::new_with_str_sequence_and_options(array.as_ref(), &options)
+        .map_err(|e| format!("{:?}", e))?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob).map_err(|e| format!("{:?}", e))?;
+
+    let document = web_sys::window()
+        .and_then(|w| w.document())
+        .ok_or("No document available")?;
+    let anchor = document
+        .create_element("a")
+        .map_err(|e| format!("{:?}", e))?
+        .dyn_into::<web_sys::HtmlAnchorElement>()
+        .map_err(|e| format!("{:?}", e))?;
+    anchor.set_href(&url);
+    anchor.set_download(filename);
+    anchor.click();
+
+    web_sys::Url::revoke_object_url(&url).map_err(|e| format!("{:?}", e))
+}
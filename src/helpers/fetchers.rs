@@ -1,8 +1,61 @@
-use crate::helpers::{refresh, AppState};
+use crate::helpers::{refresh, AppState, Auth};
+use futures::channel::oneshot;
+use futures::future::{self, Either};
 use gloo_net::http;
+use gloo_timers::future::TimeoutFuture;
 use poll_promise::Promise;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::rc::{Rc, Weak};
 use std::sync::{Arc, Mutex};
-use web_sys::{FormData, RequestCredentials};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use web_sys::{FormData, ProgressEvent, RequestCredentials, XmlHttpRequest};
+
+pub const DEFAULT_TIMEOUT_SECONDS: u32 = 15;
+pub const TIMEOUT_ERROR: &str = "Request timed out";
+/// What a request fails with once refreshing the login didn't get it through.
+pub const AUTH_ERROR: &str = "Authentication failed";
+/// How long to back off after a 429 that didn't say, or said in a way we can't read.
+/// Browsers hide `Retry-After` from cross-origin requests unless the backend exposes it.
+pub const DEFAULT_RETRY_AFTER_SECONDS: u32 = 30;
+/// Shortest back off after a 429, so `Retry-After: 0` can't turn into a request every frame.
+pub const MIN_RETRY_AFTER_SECONDS: u32 = 1;
+
+/// How often a request is retried when the server can't be reached at all.
+/// Responses from the server, including 4xx and 401, are never retried here.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub base_delay_ms: u32,
+}
+
+impl RetryPolicy {
+    /// Delay before retry number `attempt` (starting at 0), doubling each time.
+    pub fn delay_ms(&self, attempt: u32) -> u32 {
+        self.base_delay_ms
+            .saturating_mul(2u32.saturating_pow(attempt))
+    }
+}
+
+/// How much of a form upload has gone out so far.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct UploadProgress {
+    pub sent: u64,
+    /// `None` when the browser can't tell how big the upload is
+    pub total: Option<u64>,
+}
+
+impl UploadProgress {
+    /// Between 0 and 1, or `None` if there's no total to measure against.
+    pub fn fraction(&self) -> Option<f32> {
+        match self.total {
+            Some(total) if total > 0 => Some((self.sent as f32 / total as f32).min(1.0)),
+            _ => None,
+        }
+    }
+}
 
 #[derive(Clone, Debug)]
 pub enum RequestStatus {
@@ -33,10 +86,53 @@ enum FetchResponse {
     Success(RequestStatus),
     Failure(String),
     FailAuth,
+    /// 429, with the seconds to wait before asking again
+    RateLimited(u32),
+}
+
+type FetchResult = Promise<Result<FetchResponse, String>>;
+type FetchPromise = Rc<FetchResult>;
+
+thread_local! {
+    /// GETs still waiting on the backend, so an identical one can join instead of resending.
+    /// Entries go once a requestor sees their answer.
+    static IN_FLIGHT: RefCell<HashMap<String, Weak<FetchResult>>> = RefCell::new(HashMap::new());
+}
+
+fn in_flight_key(url: &str, with_credentials: bool) -> String {
+    format!("{}|{}", with_credentials, url)
+}
+
+/// A GET for the same thing that hasn't been answered yet.
+fn join_in_flight(key: &str) -> Option<FetchPromise> {
+    IN_FLIGHT.with(|in_flight| {
+        let promise = in_flight.borrow().get(key)?.upgrade()?;
+        promise.ready().is_none().then_some(promise)
+    })
+}
+
+fn add_in_flight(key: String, promise: &FetchPromise) {
+    IN_FLIGHT.with(|in_flight| {
+        let mut in_flight = in_flight.borrow_mut();
+        in_flight.retain(|_, p| p.strong_count() > 0);
+        in_flight.insert(key, Rc::downgrade(promise));
+    });
+}
+
+fn remove_in_flight(key: &str, promise: &FetchPromise) {
+    IN_FLIGHT.with(|in_flight| {
+        let mut in_flight = in_flight.borrow_mut();
+        let same = in_flight
+            .get(key)
+            .map_or(false, |p| p.as_ptr() == Rc::as_ptr(promise));
+        if same {
+            in_flight.remove(key);
+        }
+    });
 }
 
 pub struct Requestor {
-    promise: Option<Promise<Result<FetchResponse, String>>>,
+    promise: Option<FetchPromise>,
     with_credentials: bool,
     url: String,
     retry_count: usize,
@@ -45,6 +141,10 @@ pub struct Requestor {
     post_data: Option<String>,
     form_data: Option<FormData>,
     method: Method,
+    timeout_seconds: u32,
+    retry: RetryPolicy,
+    upload_progress: Arc<Mutex<Option<UploadProgress>>>,
+    retry_after: Option<u32>,
     pub app_state: Arc<Mutex<AppState>>,
 }
 
@@ -97,24 +197,66 @@ impl Requestor {
             post_data: data,
             form_data: form,
             method,
+            timeout_seconds: DEFAULT_TIMEOUT_SECONDS,
+            retry: RetryPolicy::default(),
+            upload_progress: Arc::new(Mutex::new(None)),
+            retry_after: None,
             app_state,
         }
     }
 
+    /// Overrides how long each attempt at a request may take before it fails with
+    /// [`TIMEOUT_ERROR`]. Form posts only fail once they've gone this long without any upload progress.
+    pub fn with_timeout(mut self, seconds: u32) -> Self {
+        self.timeout_seconds = seconds;
+        self
+    }
+
+    /// Retries up to `max_retries` times on network errors, waiting `base_delay_ms`,
+    /// then twice as long before each further attempt.
+    /// An attempt that times out is retried like any other network error.
+    pub fn with_retries(mut self, max_retries: u32, base_delay_ms: u32) -> Self {
+        self.retry = RetryPolicy {
+            max_retries,
+            base_delay_ms,
+        };
+        self
+    }
+
+    /// Progress of a form post once the browser has reported any,
+    /// other requests never have any.
+    pub fn upload_progress(&self) -> Option<UploadProgress> {
+        *self.upload_progress.lock().unwrap()
+    }
+
+    /// Seconds the server asked us to wait if the last request was rate limited.
+    pub fn retry_after(&self) -> Option<u32> {
+        self.retry_after
+    }
+
     pub fn check_promise(&mut self) -> RequestStatus {
         match refresh::check_refresh_promise(&mut self.token_refresh_promise) {
             refresh::RefreshStatus::NotStarted => {}
             refresh::RefreshStatus::InProgress => {}
-            refresh::RefreshStatus::Success => {
+            refresh::RefreshStatus::Success { lifetime, token } => {
                 log::debug!("Retrying Request");
                 AppState::set_logged_in(&self.app_state);
+                AppState::set_token_lifetime(&self.app_state, lifetime);
+                if let Some(token) = token {
+                    AppState::set_access_token(&self.app_state, token);
+                }
                 self.state_has_changed = true;
                 self.send();
                 return RequestStatus::InProgress;
             }
             refresh::RefreshStatus::Failed(_) => {
                 self.state_has_changed = true;
-                return RequestStatus::Failed("Failed to authenticate".to_string());
+                AppState::set_session_expired(&self.app_state);
+                return RequestStatus::Failed(AUTH_ERROR.to_string());
+            }
+            refresh::RefreshStatus::NetworkError(e) => {
+                self.state_has_changed = true;
+                return RequestStatus::Failed(e);
             }
         }
 
@@ -124,15 +266,23 @@ impl Requestor {
                 Some(result) => match result {
                     Ok(FetchResponse::Success(status)) => status.clone(),
                     Ok(FetchResponse::Failure(e)) => RequestStatus::Failed(e.to_string()),
+                    Ok(FetchResponse::RateLimited(seconds)) => {
+                        self.retry_after = Some(*seconds);
+                        RequestStatus::Failed(format!("Rate limited, retry in {}s", seconds))
+                    }
                     Ok(FetchResponse::FailAuth) => {
                         if self.retry_count > 0 {
                             log::debug!("Retrying auth");
                             self.retry_count -= 1;
-                            self.token_refresh_promise = refresh::submit_refresh();
+                            let app_state = self.app_state.lock().unwrap();
+                            self.token_refresh_promise = refresh::submit_refresh(
+                                &app_state.api_url("auth/refresh"),
+                                app_state.auth(),
+                            );
                             RequestStatus::InProgress
                         } else {
-                            AppState::set_logged_out(&self.app_state);
-                            RequestStatus::Failed("Authentication failed".to_string())
+                            AppState::set_session_expired(&self.app_state);
+                            RequestStatus::Failed(AUTH_ERROR.to_string())
                         }
                     }
 
@@ -145,12 +295,29 @@ impl Requestor {
         match res {
             RequestStatus::InProgress => {}
             _ => {
-                self.promise = None;
+                if let Some(promise) = self.promise.take() {
+                    remove_in_flight(&in_flight_key(&self.url, self.with_credentials), &promise);
+                }
             }
         }
         res
     }
 
+    /// For endpoints that say the login lapsed in the body rather than with a 401.
+    /// Refreshes it and sends the request again, at most once, as a 401 would.
+    /// Returns whether it's trying, the result comes from [`Self::check_promise`].
+    pub fn retry_auth(&mut self) -> bool {
+        if self.retry_count == 0 {
+            return false;
+        }
+        self.retry_count -= 1;
+        self.state_has_changed = true;
+        let app_state = self.app_state.lock().unwrap();
+        self.token_refresh_promise =
+            refresh::submit_refresh(&app_state.api_url("auth/refresh"), app_state.auth());
+        true
+    }
+
     pub fn refresh_context(&mut self) -> bool {
         match self.state_has_changed {
             true => {
@@ -164,6 +331,7 @@ impl Requestor {
 
 impl Requestor {
     pub fn send(&mut self) {
+        self.retry_after = None;
         match self.method {
             Method::Get => self.get(),
             Method::Post => self.post(),
@@ -172,20 +340,39 @@ impl Requestor {
     }
 
     fn refresh_login(&mut self) {
-        self.token_refresh_promise = refresh::submit_refresh();
+        let app_state = self.app_state.lock().unwrap();
+        self.token_refresh_promise =
+            refresh::submit_refresh(&app_state.api_url("auth/refresh"), app_state.auth());
+    }
+
+    /// The login to send along, if this request wants one.
+    fn auth(&self) -> Auth {
+        match self.with_credentials {
+            true => self.app_state.lock().unwrap().auth(),
+            false => Auth::None,
+        }
     }
 
     fn get(&mut self) {
+        let key = in_flight_key(&self.url, self.with_credentials);
+        if let Some(promise) = join_in_flight(&key) {
+            log::debug!("Joining request already in flight to {}", self.url);
+            self.promise = Some(promise);
+            return;
+        }
+
         let url = self.url.clone();
-        let with_credentials = self.with_credentials;
+        let auth = self.auth();
+        let timeout_seconds = self.timeout_seconds;
+        let retry = self.retry;
         let promise = Promise::spawn_local(async move {
-            let request = http::Request::get(&url);
-            let request = match with_credentials {
-                true => request.credentials(RequestCredentials::Include),
-                false => request,
-            };
-            let response = request.send().await.map_err(|e| e.to_string())?;
-            let text = response.text().await.map_err(|e| e.to_string())?;
+            let response = send_with_retries(retry, timeout_seconds, || {
+                authorize(http::Request::get(&url), &auth)
+                    .build()
+                    .map_err(|e| e.to_string())
+            })
+            .await?;
+            let text = read_text(&response, timeout_seconds).await?;
 
             let result = match response.status() {
                 200 => FetchResponse::Success(RequestStatus::Success(text)),
@@ -193,57 +380,480 @@ impl Requestor {
                     log::warn!("Auth Error: {}", text);
                     FetchResponse::FailAuth
                 }
-                _ => {
+                429 => {
+                    let retry_after = response.headers().get("Retry-After");
+                    let seconds = retry_after_seconds(retry_after.as_deref(), chrono::Utc::now());
+                    log::warn!("Rate limited, retrying in {}s", seconds);
+                    FetchResponse::RateLimited(seconds)
+                }
+                status => {
                     log::error!("Response: {}", text);
-                    FetchResponse::Failure(text)
+                    FetchResponse::Failure(error_message(status, &response.status_text()))
                 }
             };
             Ok(result)
         });
+        let promise = Rc::new(promise);
+        add_in_flight(key, &promise);
         self.promise = Some(promise);
     }
 
     fn post(&mut self) {
+        if let Some(form_data) = self.form_data.clone() {
+            self.post_form(form_data);
+            return;
+        }
         let url = self.url.clone();
-        let with_credentials = self.with_credentials;
+        let auth = self.auth();
         let json_data = self.post_data.clone();
-        let form_data = self.form_data.clone();
+        let timeout_seconds = self.timeout_seconds;
+        let retry = self.retry;
 
         let promise = Promise::spawn_local(async move {
-            let request = http::Request::post(&url);
-            let request = match with_credentials {
-                true => request.credentials(RequestCredentials::Include),
-                false => request,
-            };
-            let request = {
-                if let Some(data) = json_data {
+            let response = send_with_retries(retry, timeout_seconds, || {
+                let request = authorize(http::Request::post(&url), &auth);
+                if let Some(data) = json_data.clone() {
                     request
                         .header("Content-Type", "application/json")
                         .body(data)
-                        .map_err(|e| e.to_string())?
-                } else if let Some(data) = form_data {
-                    request.body(data).map_err(|e| e.to_string())?
+                        .map_err(|e| e.to_string())
                 } else {
-                    request.build().map_err(|e| e.to_string())?
+                    request.build().map_err(|e| e.to_string())
                 }
-            };
+            })
+            .await?;
+            let text = read_text(&response, timeout_seconds).await?;
+            Ok(post_response(response.status(), text))
+        });
+        self.promise = Some(Rc::new(promise));
+    }
 
-            let response = request.send().await.map_err(|e| e.to_string())?;
-            let text = response.text().await.map_err(|e| e.to_string())?;
+    /// Form posts carry whole binaries, so they go through XMLHttpRequest,
+    /// which reports upload progress where fetch doesn't.
+    /// A slow upload can take well past the timeout, so it only counts while nothing is moving.
+    fn post_form(&mut self, form_data: FormData) {
+        let url = self.url.clone();
+        let auth = self.auth();
+        let timeout_seconds = self.timeout_seconds;
+        let progress = Arc::clone(&self.upload_progress);
+        *progress.lock().unwrap() = None;
 
-            let result = match response.status() {
-                200 => FetchResponse::Success(RequestStatus::Success(text)),
-                401 => {
-                    log::warn!("Auth Error: {}", text);
-                    FetchResponse::FailAuth
-                }
-                _ => {
-                    log::error!("Response: {}", text);
-                    FetchResponse::Failure(text)
+        let promise = Promise::spawn_local(async move {
+            let (status, text) =
+                send_form(&url, &auth, &form_data, progress, timeout_seconds).await?;
+            Ok(post_response(status, text))
+        });
+        self.promise = Some(Rc::new(promise));
+    }
+}
+
+/// Sends the session cookie, or the token as an `Authorization` header.
+pub fn authorize(request: http::RequestBuilder, auth: &Auth) -> http::RequestBuilder {
+    match auth {
+        Auth::None => request,
+        Auth::Cookie => request.credentials(RequestCredentials::Include),
+        Auth::Bearer(token) => request.header("Authorization", &format!("Bearer {}", token)),
+    }
+}
+
+fn post_response(status: u16, text: String) -> FetchResponse {
+    match status {
+        200 => FetchResponse::Success(RequestStatus::Success(text)),
+        401 => {
+            log::warn!("Auth Error: {}", text);
+            FetchResponse::FailAuth
+        }
+        _ => {
+            log::error!("Response: {}", text);
+            FetchResponse::Failure(text)
+        }
+    }
+}
+
+/// Keeps the XMLHttpRequest callbacks alive while the request is out.
+/// Dropping it, e.g. on a timeout, aborts the request so the callbacks are never called.
+struct PendingForm {
+    xhr: XmlHttpRequest,
+    _on_progress: Closure<dyn FnMut(ProgressEvent)>,
+    _on_done: [Closure<dyn FnMut()>; 2],
+}
+
+impl Drop for PendingForm {
+    fn drop(&mut self) {
+        self.xhr.set_onload(None);
+        self.xhr.set_onerror(None);
+        if let Ok(upload) = self.xhr.upload() {
+            upload.set_onprogress(None);
+        }
+        let _ = self.xhr.abort();
+    }
+}
+
+fn js_error(e: JsValue) -> String {
+    e.as_string().unwrap_or_else(|| format!("{:?}", e))
+}
+
+/// Posts `form` to `url`, writing into `progress` as the browser reports it.
+/// Gives back the status code and body, or [`TIMEOUT_ERROR`] once `idle_seconds`
+/// go by without the upload moving.
+async fn send_form(
+    url: &str,
+    auth: &Auth,
+    form: &FormData,
+    progress: Arc<Mutex<Option<UploadProgress>>>,
+    idle_seconds: u32,
+) -> Result<(u16, String), String> {
+    let xhr = XmlHttpRequest::new().map_err(js_error)?;
+    xhr.open_with_async("POST", url, true).map_err(js_error)?;
+    match auth {
+        Auth::None => {}
+        Auth::Cookie => xhr.set_with_credentials(true),
+        Auth::Bearer(token) => xhr
+            .set_request_header("Authorization", &format!("Bearer {}", token))
+            .map_err(js_error)?,
+    }
+
+    let reported = Arc::clone(&progress);
+    let on_progress = Closure::<dyn FnMut(ProgressEvent)>::new(move |event: ProgressEvent| {
+        *reported.lock().unwrap() = Some(UploadProgress {
+            sent: event.loaded() as u64,
+            total: event.length_computable().then(|| event.total() as u64),
+        });
+    });
+    xhr.upload()
+        .map_err(js_error)?
+        .set_onprogress(Some(on_progress.as_ref().unchecked_ref()));
+
+    let (sender, receiver) = oneshot::channel::<bool>();
+    let sender = Rc::new(RefCell::new(Some(sender)));
+    let on_done = |loaded: bool| {
+        let sender = Rc::clone(&sender);
+        Closure::<dyn FnMut()>::new(move || {
+            if let Some(sender) = sender.borrow_mut().take() {
+                let _ = sender.send(loaded);
+            }
+        })
+    };
+    let on_done = [on_done(true), on_done(false)];
+    xhr.set_onload(Some(on_done[0].as_ref().unchecked_ref()));
+    xhr.set_onerror(Some(on_done[1].as_ref().unchecked_ref()));
+
+    let pending = PendingForm {
+        xhr,
+        _on_progress: on_progress,
+        _on_done: on_done,
+    };
+    pending
+        .xhr
+        .send_with_opt_form_data(Some(form))
+        .map_err(js_error)?;
+
+    let mut done = receiver;
+    let mut last_sent = None;
+    let loaded = loop {
+        let timer = TimeoutFuture::new(idle_seconds.saturating_mul(1000));
+        match future::select(done, timer).await {
+            Either::Left((loaded, _)) => break loaded.unwrap_or(false),
+            Either::Right((_, still_waiting)) => {
+                let sent = progress.lock().unwrap().map(|p| p.sent);
+                if sent == last_sent {
+                    return Err(TIMEOUT_ERROR.to_string());
                 }
-            };
-            Ok(result)
+                last_sent = sent;
+                done = still_waiting;
+            }
+        }
+    };
+    if !loaded {
+        return Err("Unable to reach server".to_string());
+    }
+    let status = pending.xhr.status().map_err(js_error)?;
+    let text = pending
+        .xhr
+        .response_text()
+        .map_err(js_error)?
+        .unwrap_or_default();
+    Ok((status, text))
+}
+
+/// Why a [`Getter`] came back empty handed.
+#[derive(Clone, Debug, PartialEq)]
+pub enum GetError {
+    /// The request itself failed, as in [`RequestStatus::Failed`]
+    Request(String),
+    /// The answer wasn't the JSON that was expected
+    InvalidData(String),
+    /// The server wants to be left alone for this many seconds
+    RateLimited(u32),
+}
+
+impl std::fmt::Display for GetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GetError::Request(e) => write!(f, "{}", e),
+            GetError::InvalidData(e) => write!(f, "Invalid data: {}", e),
+            GetError::RateLimited(seconds) => write!(f, "Rate limited, retry in {}s", seconds),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum GetStatus<T> {
+    NotStarted,
+    InProgress,
+    Success(T),
+    Failed(GetError),
+}
+
+/// A GET whose JSON answer is parsed into `T`.
+/// Raw text, like markdown, still goes through a [`Requestor`] directly.
+pub struct Getter<T> {
+    requestor: Requestor,
+    /// What an empty body stands for, if it's allowed
+    empty: Option<fn() -> T>,
+    data: PhantomData<T>,
+}
+
+impl<T: serde::de::DeserializeOwned> Getter<T> {
+    pub fn new(app_state: Arc<Mutex<AppState>>, url: &str, with_credentials: bool) -> Self {
+        Self {
+            requestor: Requestor::new_get(app_state, url, with_credentials),
+            empty: None,
+            data: PhantomData,
+        }
+    }
+
+    /// See [`Requestor::with_retries`].
+    pub fn with_retries(mut self, max_retries: u32, base_delay_ms: u32) -> Self {
+        self.requestor = self.requestor.with_retries(max_retries, base_delay_ms);
+        self
+    }
+
+    pub fn send(&mut self) {
+        self.requestor.send();
+    }
+
+    pub fn check_promise(&mut self) -> GetStatus<T> {
+        match self.requestor.check_promise() {
+            RequestStatus::NotStarted => GetStatus::NotStarted,
+            RequestStatus::InProgress => GetStatus::InProgress,
+            RequestStatus::Success(text) => match parse_json(&text, self.empty) {
+                Ok(data) => GetStatus::Success(data),
+                Err(e) => GetStatus::Failed(e),
+            },
+            RequestStatus::Failed(e) => match self.requestor.retry_after() {
+                Some(seconds) => GetStatus::Failed(GetError::RateLimited(seconds)),
+                None => GetStatus::Failed(GetError::Request(e)),
+            },
+        }
+    }
+
+    pub fn refresh_context(&mut self) -> bool {
+        self.requestor.refresh_context()
+    }
+}
+
+impl<T: serde::de::DeserializeOwned + Default> Getter<T> {
+    /// Takes a 200 with an empty body as `T::default()`, such as an empty list,
+    /// instead of failing on it as invalid JSON.
+    pub fn empty_as_default(mut self) -> Self {
+        self.empty = Some(T::default);
+        self
+    }
+}
+
+fn parse_json<T: serde::de::DeserializeOwned>(
+    text: &str,
+    empty: Option<fn() -> T>,
+) -> Result<T, GetError> {
+    match empty {
+        Some(empty) if text.trim().is_empty() => Ok(empty()),
+        _ => serde_json::from_str(text).map_err(|e| GetError::InvalidData(e.to_string())),
+    }
+}
+
+/// Races `request` against a timer so a stalled request can't leave the UI waiting forever.
+pub async fn with_timeout<T>(
+    seconds: u32,
+    request: impl Future<Output = Result<T, String>>,
+) -> Result<T, String> {
+    let timer = TimeoutFuture::new(seconds.saturating_mul(1000));
+    match future::select(Box::pin(request), timer).await {
+        Either::Left((result, _)) => result,
+        Either::Right(_) => Err(TIMEOUT_ERROR.to_string()),
+    }
+}
+
+/// e.g. "Error 503: Service Unavailable". Browsers leave the status text empty over HTTP/2.
+pub fn error_message(status: u16, status_text: &str) -> String {
+    match status_text.trim() {
+        "" => format!("Error {}", status),
+        status_text => format!("Error {}: {}", status, status_text),
+    }
+}
+
+/// `Retry-After` is either a number of seconds or an HTTP date to wait until.
+/// Never less than [`MIN_RETRY_AFTER_SECONDS`].
+pub fn retry_after_seconds(header: Option<&str>, now: chrono::DateTime<chrono::Utc>) -> u32 {
+    let Some(header) = header.map(str::trim) else {
+        return DEFAULT_RETRY_AFTER_SECONDS;
+    };
+    let seconds = match header.parse::<u32>() {
+        Ok(seconds) => seconds,
+        Err(_) => match chrono::DateTime::parse_from_rfc2822(header) {
+            Ok(at) => (at.with_timezone(&chrono::Utc) - now)
+                .num_seconds()
+                .clamp(0, u32::MAX as i64) as u32,
+            Err(_) => DEFAULT_RETRY_AFTER_SECONDS,
+        },
+    };
+    seconds.max(MIN_RETRY_AFTER_SECONDS)
+}
+
+/// Sends the request built by `build`, rebuilding and resending it after a network error
+/// or timeout as long as `retry` allows. Each attempt gets `timeout_seconds` of its own,
+/// so one stalled attempt can't use up the time meant for the retries.
+pub async fn send_with_retries(
+    retry: RetryPolicy,
+    timeout_seconds: u32,
+    build: impl Fn() -> Result<http::Request, String>,
+) -> Result<http::Response, String> {
+    let mut attempt = 0;
+    loop {
+        let request = build()?;
+        let sent = with_timeout(timeout_seconds, async {
+            request
+                .send()
+                .await
+                .map_err(|e| format!("Unable to reach server: {}", e))
         });
-        self.promise = Some(promise);
+        match sent.await {
+            Ok(response) => return Ok(response),
+            Err(e) if attempt < retry.max_retries => {
+                let delay = retry.delay_ms(attempt);
+                log::warn!("{}, retrying in {}ms", e, delay);
+                TimeoutFuture::new(delay).await;
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Reads the body of a response, which can stall just like sending the request.
+pub async fn read_text(response: &http::Response, timeout_seconds: u32) -> Result<String, String> {
+    with_timeout(timeout_seconds, async {
+        response
+            .text()
+            .await
+            .map_err(|e| format!("Unable to read response: {}", e))
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failed_refresh_expires_session() {
+        let app_state = Arc::new(Mutex::new(AppState::default()));
+        AppState::set_logged_in(&app_state);
+        let mut requestor = Requestor::new_get(Arc::clone(&app_state), "scores", true);
+        requestor.token_refresh_promise = Some(Promise::from_ready(Ok(refresh::RefreshResponse {
+            status: "fail".to_string(),
+            message: "Not authorized".to_string(),
+            expires_in: None,
+            access_token: None,
+        })));
+
+        assert!(matches!(
+            requestor.check_promise(),
+            RequestStatus::Failed(e) if e == AUTH_ERROR
+        ));
+        let app_state = app_state.lock().unwrap();
+        assert!(app_state.session_expired);
+        assert!(matches!(
+            app_state.logged_in,
+            crate::helpers::LoginState::LoggedOut
+        ));
+    }
+
+    #[test]
+    fn test_backoff_schedule() {
+        let retry = RetryPolicy {
+            max_retries: 3,
+            base_delay_ms: 250,
+        };
+        let delays: Vec<u32> = (0..retry.max_retries).map(|a| retry.delay_ms(a)).collect();
+        assert_eq!(delays, vec![250, 500, 1000]);
+        assert_eq!(retry.delay_ms(40), u32::MAX);
+    }
+
+    #[test]
+    fn test_upload_fraction() {
+        let progress = |sent, total| UploadProgress { sent, total };
+        assert_eq!(progress(50, Some(200)).fraction(), Some(0.25));
+        assert_eq!(progress(300, Some(200)).fraction(), Some(1.0));
+        assert_eq!(progress(50, Some(0)).fraction(), None);
+        assert_eq!(progress(50, None).fraction(), None);
+    }
+
+    #[test]
+    fn test_parse_json() {
+        assert_eq!(parse_json::<Vec<u32>>("[1, 2]", None), Ok(vec![1, 2]));
+        assert!(matches!(
+            parse_json::<Vec<u32>>("not json", None),
+            Err(GetError::InvalidData(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_empty_body() {
+        assert!(matches!(
+            parse_json::<Vec<u32>>("", None),
+            Err(GetError::InvalidData(_))
+        ));
+        assert_eq!(parse_json::<Vec<u32>>("", Some(Vec::new)), Ok(vec![]));
+        assert_eq!(parse_json::<Vec<u32>>(" \r\n", Some(Vec::new)), Ok(vec![]));
+        // Only an empty body is let through, not a garbled one
+        assert!(matches!(
+            parse_json::<Vec<u32>>("\u{FFFD}\u{FFFD}", Some(Vec::new)),
+            Err(GetError::InvalidData(_))
+        ));
+    }
+
+    #[test]
+    fn test_retry_after() {
+        let now = chrono::DateTime::parse_from_rfc3339("2015-10-21T07:28:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        assert_eq!(retry_after_seconds(Some("12"), now), 12);
+        assert_eq!(
+            retry_after_seconds(Some(" 0 "), now),
+            MIN_RETRY_AFTER_SECONDS
+        );
+        assert_eq!(
+            retry_after_seconds(Some("Wed, 21 Oct 2015 07:28:45 GMT"), now),
+            45
+        );
+        assert_eq!(
+            retry_after_seconds(Some("Wed, 21 Oct 2015 07:27:00 GMT"), now),
+            MIN_RETRY_AFTER_SECONDS
+        );
+        assert_eq!(
+            retry_after_seconds(Some("soon"), now),
+            DEFAULT_RETRY_AFTER_SECONDS
+        );
+        assert_eq!(retry_after_seconds(None, now), DEFAULT_RETRY_AFTER_SECONDS);
+    }
+
+    #[test]
+    fn test_error_message() {
+        assert_eq!(
+            error_message(503, "Service Unavailable"),
+            "Error 503: Service Unavailable"
+        );
+        assert_eq!(error_message(404, ""), "Error 404");
     }
 }
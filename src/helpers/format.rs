@@ -0,0 +1,172 @@
+/// How digits are grouped and where the decimal point goes.
+#[derive(Debug, Clone, Copy, PartialEq, Default, serde::Deserialize, serde::Serialize)]
+pub enum NumberFormat {
+    /// No grouping, as the numbers come from the backend
+    #[default]
+    Plain,
+    Comma,
+    Dot,
+    Space,
+}
+
+impl NumberFormat {
+    pub fn iter() -> impl Iterator<Item = Self> {
+        use NumberFormat::*;
+        [Plain, Comma, Dot, Space].into_iter()
+    }
+
+    /// Thousands separator, if any, and decimal separator.
+    fn separators(self) -> (Option<char>, char) {
+        match self {
+            NumberFormat::Plain => (None, '.'),
+            NumberFormat::Comma => (Some(','), '.'),
+            NumberFormat::Dot => (Some('.'), ','),
+            NumberFormat::Space => (Some(' '), ','),
+        }
+    }
+}
+
+impl std::fmt::Display for NumberFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let example = Formatting {
+            numbers: *self,
+            ..Default::default()
+        };
+        write!(f, "{}", example.decimal(1234567.89, 2))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, serde::Deserialize, serde::Serialize)]
+pub enum ClockFormat {
+    #[default]
+    Hours24,
+    Hours12,
+}
+
+impl ClockFormat {
+    pub fn iter() -> impl Iterator<Item = Self> {
+        [ClockFormat::Hours24, ClockFormat::Hours12].into_iter()
+    }
+}
+
+impl std::fmt::Display for ClockFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClockFormat::Hours24 => write!(f, "24 hour"),
+            ClockFormat::Hours12 => write!(f, "12 hour"),
+        }
+    }
+}
+
+/// The user's preferences for showing numbers and times. Durations still go
+/// through [`scoreboard_db::NiceTime`], this only covers plain numbers and dates.
+#[derive(Debug, Clone, Copy, PartialEq, Default, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct Formatting {
+    pub numbers: NumberFormat,
+    pub clock: ClockFormat,
+}
+
+impl Formatting {
+    pub fn count(&self, n: usize) -> String {
+        self.decimal(n as f64, 0)
+    }
+
+    pub fn decimal(&self, value: f64, places: usize) -> String {
+        let (thousands, point) = self.numbers.separators();
+        let text = format!("{:.*}", places, value.abs());
+        let (whole, fraction) = text.split_once('.').unwrap_or((&text, ""));
+
+        let mut out = String::new();
+        if value.is_sign_negative() && value != 0.0 {
+            out.push('-');
+        }
+        for (i, digit) in whole.chars().enumerate() {
+            if let (Some(separator), true) = (thousands, i > 0 && (whole.len() - i) % 3 == 0) {
+                out.push(separator);
+            }
+            out.push(digit);
+        }
+        if !fraction.is_empty() {
+            out.push(point);
+            out.push_str(fraction);
+        }
+        out
+    }
+
+    pub fn date_time(&self, at: &chrono::DateTime<chrono::Utc>) -> String {
+        let format = match self.clock {
+            ClockFormat::Hours24 => "%Y-%m-%d %H:%M:%S UTC",
+            ClockFormat::Hours12 => "%Y-%m-%d %I:%M:%S %p UTC",
+        };
+        at.format(format).to_string()
+    }
+}
+
+/// Rough age of something that happened `seconds` ago, e.g. "3m ago".
+pub fn time_ago(seconds: i64) -> String {
+    match seconds {
+        s if s < 60 => "just now".to_string(),
+        s if s < 60 * 60 => format!("{}m ago", s / 60),
+        s if s < 24 * 60 * 60 => format!("{}h ago", s / (60 * 60)),
+        s => format!("{}d ago", s / (24 * 60 * 60)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbers(numbers: NumberFormat) -> Formatting {
+        Formatting {
+            numbers,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_separators() {
+        assert_eq!(
+            numbers(NumberFormat::Plain).decimal(1234567.891, 2),
+            "1234567.89"
+        );
+        assert_eq!(
+            numbers(NumberFormat::Comma).decimal(1234567.891, 2),
+            "1,234,567.89"
+        );
+        assert_eq!(
+            numbers(NumberFormat::Dot).decimal(1234567.891, 2),
+            "1.234.567,89"
+        );
+        assert_eq!(numbers(NumberFormat::Space).decimal(-1234.5, 1), "-1 234,5");
+        assert_eq!(numbers(NumberFormat::Comma).count(999), "999");
+        assert_eq!(numbers(NumberFormat::Comma).count(1000), "1,000");
+        assert_eq!(numbers(NumberFormat::Dot).count(0), "0");
+        assert_eq!(NumberFormat::Comma.to_string(), "1,234,567.89");
+    }
+
+    #[test]
+    fn test_clock() {
+        let at = chrono::DateTime::parse_from_rfc3339("2023-11-05T15:04:05Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        assert_eq!(
+            Formatting::default().date_time(&at),
+            "2023-11-05 15:04:05 UTC"
+        );
+        let twelve = Formatting {
+            clock: ClockFormat::Hours12,
+            ..Default::default()
+        };
+        assert_eq!(twelve.date_time(&at), "2023-11-05 03:04:05 PM UTC");
+    }
+
+    #[test]
+    fn test_time_ago() {
+        assert_eq!(time_ago(-5), "just now");
+        assert_eq!(time_ago(59), "just now");
+        assert_eq!(time_ago(3 * 60 + 10), "3m ago");
+        assert_eq!(time_ago(2 * 60 * 60), "2h ago");
+        assert_eq!(time_ago(3 * 24 * 60 * 60), "3d ago");
+    }
+}
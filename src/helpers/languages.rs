@@ -33,6 +33,54 @@ impl Languages {
         .iter()
         .copied()
     }
+
+    /// Parses the list of languages the backend accepts, e.g. `["Rust", "Python"]`.
+    /// Names this build doesn't know are skipped, and `None` means nothing usable was found.
+    pub fn list_from_json(json: &str) -> Option<Vec<Self>> {
+        let names: Vec<String> = serde_json::from_str(json).ok()?;
+        let languages: Vec<Self> = names
+            .into_iter()
+            .filter_map(|name| serde_json::from_value(serde_json::Value::String(name)).ok())
+            .collect();
+        match languages.is_empty() {
+            true => None,
+            false => Some(languages),
+        }
+    }
+
+    /// Finds a language by the name the backend uses for it, e.g. `"C++"` or `"Cpp"`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::iter().find(|l| {
+            l.to_string().eq_ignore_ascii_case(name)
+                || format!("{:?}", l).eq_ignore_ascii_case(name)
+        })
+    }
+
+    /// Source file extensions for each language, without the leading dot.
+    pub fn extensions(&self) -> &'static [&'static str] {
+        match self {
+            Languages::C => &["c", "h"],
+            Languages::Cpp => &["cpp", "cc", "cxx", "hpp"],
+            Languages::CSharp => &["cs"],
+            Languages::Go => &["go"],
+            Languages::Java => &["java"],
+            Languages::JavaScript => &["js", "mjs"],
+            Languages::Python => &["py"],
+            Languages::Rust => &["rs"],
+            Languages::ShellScript => &["sh", "bash"],
+        }
+    }
+
+    /// The usual extension for the language, e.g. for naming files or picking a highlighter.
+    pub fn extension(&self) -> &'static str {
+        self.extensions()[0]
+    }
+
+    /// The language a file extension belongs to, with or without the leading dot.
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        let extension = extension.trim_start_matches('.').to_lowercase();
+        Self::iter().find(|l| l.extensions().contains(&extension.as_str()))
+    }
 }
 
 impl Display for Languages {
@@ -50,3 +98,42 @@ impl Display for Languages {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_from_json() {
+        assert_eq!(
+            Languages::list_from_json(r#"["Rust", "Python", "Cobol"]"#),
+            Some(vec![Languages::Rust, Languages::Python])
+        );
+        assert_eq!(Languages::list_from_json(r#"["Cobol"]"#), None);
+        assert_eq!(Languages::list_from_json("not json"), None);
+    }
+
+    #[test]
+    fn test_extension_round_trip() {
+        for language in Languages::iter() {
+            assert!(!language.extension().is_empty());
+            assert_eq!(
+                Languages::from_extension(language.extension()),
+                Some(language)
+            );
+            for extension in language.extensions() {
+                assert_eq!(Languages::from_extension(extension), Some(language));
+            }
+        }
+        assert_eq!(Languages::from_extension(".RS"), Some(Languages::Rust));
+        assert_eq!(Languages::from_extension("exe"), None);
+    }
+
+    #[test]
+    fn test_from_name() {
+        assert_eq!(Languages::from_name("C++"), Some(Languages::Cpp));
+        assert_eq!(Languages::from_name("cpp"), Some(Languages::Cpp));
+        assert_eq!(Languages::from_name("Bash"), Some(Languages::ShellScript));
+        assert_eq!(Languages::from_name("Cobol"), None);
+    }
+}
@@ -0,0 +1,68 @@
+/// Percent-encodes everything but the unreserved characters, for query values.
+pub fn url_encode(text: &str) -> String {
+    text.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            b => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// Undoes [`url_encode`], and takes `+` as a space like forms do.
+pub fn url_decode(text: &str) -> Option<String> {
+    let mut bytes = Vec::new();
+    let mut input = text.bytes();
+    while let Some(b) = input.next() {
+        match b {
+            b'%' => {
+                let hex = [input.next()?, input.next()?];
+                bytes.push(u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()?);
+            }
+            b'+' => bytes.push(b' '),
+            b => bytes.push(b),
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+/// The decoded value of `key` in a query string, with or without the leading `?`.
+pub fn query_param(query: &str, key: &str) -> Option<String> {
+    query
+        .trim_start_matches('?')
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(k, _)| *k == key)
+        .and_then(|(_, value)| url_decode(value))
+}
+
+/// The query string and the address without it, of the page we're running on.
+#[cfg(target_arch = "wasm32")]
+pub fn page_location() -> Option<(String, String)> {
+    let location = web_sys::window()?.location();
+    let url = format!("{}{}", location.origin().ok()?, location.pathname().ok()?);
+    Some((url, location.search().ok()?))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn page_location() -> Option<(String, String)> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_param() {
+        let query = "?panel=%F0%9F%93%96%20Challenge%20Info&challenge=2331";
+        assert_eq!(
+            query_param(query, "panel").as_deref(),
+            Some("📖 Challenge Info")
+        );
+        assert_eq!(query_param(query, "challenge").as_deref(), Some("2331"));
+        assert_eq!(query_param(query, "filter"), None);
+        assert_eq!(query_param("", "panel"), None);
+    }
+}
@@ -0,0 +1,260 @@
+/// A heading and everything up to the next heading.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Section {
+    /// Zero for the text before the first heading.
+    pub level: usize,
+    pub title: String,
+    pub body: String,
+}
+
+/// Markdown broken up so code blocks can be handled on their own.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Block {
+    Text(String),
+    Code {
+        /// The block as written, fences included.
+        markdown: String,
+        /// Just the contents between the fences.
+        code: String,
+    },
+}
+
+fn is_fence(line: &str) -> bool {
+    let line = line.trim_start();
+    line.starts_with("```") || line.starts_with("~~~")
+}
+
+fn heading(line: &str) -> Option<(usize, String)> {
+    let line = line.trim_start();
+    let level = line.chars().take_while(|c| *c == '#').count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    let rest = &line[level..];
+    if !rest.is_empty() && !rest.starts_with(' ') {
+        return None;
+    }
+    Some((level, rest.trim().trim_end_matches('#').trim().to_string()))
+}
+
+/// Splits markdown on its headings, ignoring anything inside code fences.
+pub fn split_sections(markdown: &str) -> Vec<Section> {
+    let mut sections = vec![Section {
+        level: 0,
+        title: String::new(),
+        body: String::new(),
+    }];
+    let mut in_fence = false;
+
+    for line in markdown.lines() {
+        if is_fence(line) {
+            in_fence = !in_fence;
+        } else if !in_fence {
+            if let Some((level, title)) = heading(line) {
+                sections.push(Section {
+                    level,
+                    title,
+                    body: String::new(),
+                });
+            }
+        }
+        let section = sections.last_mut().unwrap();
+        section.body.push_str(line);
+        section.body.push('\n');
+    }
+
+    if sections[0].body.trim().is_empty() {
+        sections.remove(0);
+    }
+    sections
+}
+
+/// Separates fenced code blocks from the surrounding text.
+pub fn split_code_blocks(markdown: &str) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut text = String::new();
+    let mut fenced = String::new();
+    let mut code = String::new();
+    let mut in_fence = false;
+
+    for line in markdown.lines() {
+        if is_fence(line) {
+            fenced.push_str(line);
+            fenced.push('\n');
+            if in_fence {
+                blocks.push(Block::Code {
+                    markdown: std::mem::take(&mut fenced),
+                    code: std::mem::take(&mut code),
+                });
+            } else if !text.is_empty() {
+                blocks.push(Block::Text(std::mem::take(&mut text)));
+            }
+            in_fence = !in_fence;
+        } else if in_fence {
+            fenced.push_str(line);
+            fenced.push('\n');
+            code.push_str(line);
+            code.push('\n');
+        } else {
+            text.push_str(line);
+            text.push('\n');
+        }
+    }
+
+    // An unterminated fence is just treated as text
+    text.push_str(&fenced);
+    if !text.is_empty() {
+        blocks.push(Block::Text(text));
+    }
+    blocks
+}
+
+/// Calls `replace` with the alt text and target of every image outside code fences,
+/// e.g. `![a chart](chart.png)`, and puts whatever it returns in the image's place.
+pub fn rewrite_images(markdown: &str, mut replace: impl FnMut(&str, &str) -> String) -> String {
+    let mut rewritten = String::new();
+    let mut in_fence = false;
+
+    for line in markdown.lines() {
+        if is_fence(line) {
+            in_fence = !in_fence;
+        }
+        let mut rest = line;
+        while let (false, Some(start)) = (in_fence, rest.find("![")) {
+            let image = &rest[start + 2..];
+            let Some((alt, after_alt)) = image.split_once("](") else {
+                break;
+            };
+            let Some((target, after)) = after_alt.split_once(')') else {
+                break;
+            };
+            rewritten.push_str(&rest[..start]);
+            rewritten.push_str(&replace(alt, target));
+            rest = after;
+        }
+        rewritten.push_str(rest);
+        rewritten.push('\n');
+    }
+    rewritten
+}
+
+/// Reads an image target relative to `base`, which ends in a `/`.
+/// Absolute URLs and data URIs are left alone, as is a title after the URL.
+pub fn resolve_url(base: &str, target: &str) -> String {
+    let (url, title) = match target.split_once(char::is_whitespace) {
+        Some((url, title)) => (url, Some(title)),
+        None => (target, None),
+    };
+    let resolved = if url.contains("://") || url.starts_with("data:") {
+        url.to_string()
+    } else if let Some(path) = url.strip_prefix('/') {
+        // Relative to the host rather than the backend's path
+        let host_start = base.find("://").map_or(0, |i| i + 3);
+        let origin_end = base[host_start..]
+            .find('/')
+            .map_or(base.len(), |i| host_start + i);
+        format!("{}/{}", &base[..origin_end], path)
+    } else {
+        format!("{}{}", base, url)
+    };
+    match title {
+        Some(title) => format!("{} {}", resolved, title),
+        None => resolved,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_sections() {
+        let markdown = " # Find Odds\n\nIntro\n\n## Problem\n\n```bash\n# not a heading\n```\n\n### Examples\n#hashtag\n";
+
+        let sections = split_sections(markdown);
+        let titles: Vec<(usize, &str)> = sections
+            .iter()
+            .map(|s| (s.level, s.title.as_str()))
+            .collect();
+
+        assert_eq!(
+            titles,
+            vec![(1, "Find Odds"), (2, "Problem"), (3, "Examples")]
+        );
+        assert!(sections[1].body.contains("# not a heading"));
+        assert!(sections[2].body.contains("#hashtag"));
+    }
+
+    #[test]
+    fn test_split_code_blocks() {
+        let markdown = "Run it:\n\n```bash\n./check.sh\n```\nor\n```\njudge -C 2331\n```\n";
+
+        let blocks = split_code_blocks(markdown);
+
+        assert_eq!(blocks.len(), 4);
+        assert_eq!(blocks[0], Block::Text("Run it:\n\n".to_string()));
+        assert_eq!(
+            blocks[1],
+            Block::Code {
+                markdown: "```bash\n./check.sh\n```\n".to_string(),
+                code: "./check.sh\n".to_string(),
+            }
+        );
+        assert_eq!(blocks[2], Block::Text("or\n".to_string()));
+        match &blocks[3] {
+            Block::Code { code, .. } => assert_eq!(code, "judge -C 2331\n"),
+            _ => panic!("expected a code block"),
+        }
+    }
+
+    #[test]
+    fn test_split_sections_keeps_preamble() {
+        let sections = split_sections("Some text\n# Heading\n");
+
+        assert_eq!(sections.len(), 2);
+        assert_eq!(sections[0].level, 0);
+        assert_eq!(sections[0].body, "Some text\n");
+    }
+
+    #[test]
+    fn test_rewrite_images() {
+        let markdown = "See ![chart](chart.png) and ![](b.png)\n```\n![kept](code.png)\n```\n";
+
+        let rewritten = rewrite_images(markdown, |alt, target| format!("<{}|{}>", alt, target));
+
+        assert_eq!(
+            rewritten,
+            "See <chart|chart.png> and <|b.png>\n```\n![kept](code.png)\n```\n"
+        );
+        assert_eq!(
+            rewrite_images("![broken](", |_, _| String::new()),
+            "![broken](\n"
+        );
+    }
+
+    #[test]
+    fn test_resolve_url() {
+        let base = "http://host:3000/judge/";
+
+        assert_eq!(
+            resolve_url(base, "img/a.png"),
+            "http://host:3000/judge/img/a.png"
+        );
+        assert_eq!(
+            resolve_url(base, "/img/a.png"),
+            "http://host:3000/img/a.png"
+        );
+        assert_eq!(
+            resolve_url(base, "a.png \"A title\""),
+            "http://host:3000/judge/a.png \"A title\""
+        );
+        assert_eq!(
+            resolve_url(base, "https://x.io/a.png"),
+            "https://x.io/a.png"
+        );
+        assert_eq!(
+            resolve_url(base, "data:image/png;base64,AA"),
+            "data:image/png;base64,AA"
+        );
+    }
+}
@@ -1,12 +1,21 @@
 mod challenges;
-pub use challenges::ChallengeCollection;
+pub use challenges::{ChallengeCollection, Difficulty};
 mod languages;
 pub use languages::Languages;
+pub mod download;
+pub mod format;
+pub mod location;
+pub mod markdown;
+pub mod panic_overlay;
 pub mod refresh;
+pub mod scores;
 pub mod submission;
+pub mod visibility;
 
 pub mod fetchers;
 
 mod app_state;
 pub use app_state::AppState;
 pub use app_state::LoginState;
+pub use app_state::{normalize_backend_url, DEFAULT_BACKEND_URL};
+pub use app_state::{Auth, AuthMode};
@@ -0,0 +1,128 @@
+use std::any::Any;
+use std::panic::Location;
+
+const OVERLAY_ID: &str = "panic_overlay";
+const OVERLAY_STYLE: &str = "position: fixed; inset: 0; z-index: 1000; display: flex; \
+    align-items: center; justify-content: center; background: rgba(0, 0, 0, 0.7); \
+    font-family: sans-serif; color: #ddd;";
+const BOX_STYLE: &str = "max-width: 40em; padding: 1.5em; border-radius: 6px; background: #27272a;";
+const DETAILS_STYLE: &str = "white-space: pre-wrap; max-height: 20em; overflow: auto; \
+    font-size: 0.85em; color: #f88;";
+
+/// What the panic said, and where, for the log and the overlay.
+fn describe(payload: &(dyn Any + Send), location: Option<&Location<'_>>) -> String {
+    let message = payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "Unknown error".to_string());
+    match location {
+        Some(l) => format!("{} at {}:{}:{}", message, l.file(), l.line(), l.column()),
+        None => message,
+    }
+}
+
+/// Chains onto whatever hook is already there, so install it after eframe's own.
+pub fn install() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        previous(info);
+        let message = describe(info.payload(), info.location());
+        log::error!("Panicked: {}", message);
+        if let Err(e) = show_overlay(&message) {
+            log::error!("Unable to show what went wrong: {}", e);
+        }
+    }));
+}
+
+fn js_error(e: wasm_bindgen::JsValue) -> String {
+    e.as_string().unwrap_or_else(|| format!("{:?}", e))
+}
+
+/// egui can't draw anything once it has panicked, so the way out is plain HTML
+/// over the frozen canvas. The buttons are inline script for the same reason,
+/// nothing can be called back into afterwards.
+fn show_overlay(message: &str) -> Result<(), String> {
+    let document = web_sys::window()
+        .and_then(|w| w.document())
+        .ok_or("No document available")?;
+    // One is plenty if something else panics on the way down
+    if document.get_element_by_id(OVERLAY_ID).is_some() {
+        return Ok(());
+    }
+    let body = document.body().ok_or("No body to show it in")?;
+    let element = |tag: &str, style: Option<&str>, text: Option<&str>| {
+        let element = document.create_element(tag).map_err(js_error)?;
+        if let Some(style) = style {
+            element.set_attribute("style", style).map_err(js_error)?;
+        }
+        element.set_text_content(text);
+        Ok::<_, String>(element)
+    };
+    let button = |label: &str, on_click: &str| {
+        let button = element("button", Some("margin-right: 0.5em;"), Some(label))?;
+        button
+            .set_attribute("onclick", on_click)
+            .map_err(js_error)?;
+        Ok::<_, String>(button)
+    };
+
+    let overlay = element("div", Some(OVERLAY_STYLE), None)?;
+    overlay.set_id(OVERLAY_ID);
+    let panel = element("div", Some(BOX_STYLE), None)?;
+    let details = element("details", None, None)?;
+    for child in [
+        element("summary", None, Some("Error details"))?,
+        element("pre", Some(DETAILS_STYLE), Some(message))?,
+    ] {
+        details.append_child(&child).map_err(js_error)?;
+    }
+    for child in [
+        element("h2", None, Some("Something went wrong"))?,
+        element(
+            "p",
+            None,
+            Some(
+                "The app ran into a problem and stopped. Reloading usually sorts it out, \
+                 resetting also forgets your saved settings and open windows.",
+            ),
+        )?,
+        button("Reload", "location.reload()")?,
+        button(
+            "Reset and reload",
+            "localStorage.clear(); location.reload()",
+        )?,
+        details,
+    ] {
+        panel.append_child(&child).map_err(js_error)?;
+    }
+    overlay.append_child(&panel).map_err(js_error)?;
+    body.append_child(&overlay).map_err(js_error)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe() {
+        let payload = std::panic::catch_unwind(|| panic!("boom")).unwrap_err();
+        assert_eq!(describe(payload.as_ref(), None), "boom");
+
+        let payload = std::panic::catch_unwind(|| panic!("{} went wrong", 2)).unwrap_err();
+        let location = Location::caller();
+        assert_eq!(
+            describe(payload.as_ref(), Some(location)),
+            format!(
+                "2 went wrong at {}:{}:{}",
+                location.file(),
+                location.line(),
+                location.column()
+            )
+        );
+
+        let payload = std::panic::catch_unwind(|| std::panic::panic_any(7)).unwrap_err();
+        assert_eq!(describe(payload.as_ref(), None), "Unknown error");
+    }
+}
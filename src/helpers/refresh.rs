@@ -1,3 +1,4 @@
+use crate::helpers::{fetchers::authorize, Auth};
 use gloo_net::http;
 use poll_promise::Promise;
 use web_sys::RequestCredentials;
@@ -6,33 +7,82 @@ use web_sys::RequestCredentials;
 pub struct RefreshResponse {
     pub status: String,
     pub message: String,
+    /// Seconds until the new access token expires, if the backend tells us
+    #[serde(default)]
+    pub expires_in: Option<i64>,
+    /// A new token, from backends that authenticate with one rather than a cookie
+    #[serde(default)]
+    pub access_token: Option<String>,
 }
 pub enum RefreshStatus {
     NotStarted,
     InProgress,
-    Success,
+    Success {
+        lifetime: Option<chrono::Duration>,
+        token: Option<String>,
+    },
+    /// The backend turned us away, only logging in again will help
     Failed(String),
+    /// The backend couldn't be reached or made no sense, worth trying again
+    NetworkError(String),
 }
 
 pub type RefreshPromise = Option<Promise<Result<RefreshResponse, String>>>;
 
-pub fn submit_refresh() -> RefreshPromise {
-    let url = option_env!("BACKEND_URL")
-        .unwrap_or("http://123.4.5.6:3000/")
-        .to_string();
-    let url = format!("{}api/auth/refresh", url);
+/// Refreshes the login at `url`, i.e. `AppState::api_url("auth/refresh")`.
+/// The refresh cookie always goes along, even in bearer mode, as the token
+/// isn't kept across reloads.
+pub fn submit_refresh(url: &str, auth: Auth) -> RefreshPromise {
+    let url = url.to_string();
     log::debug!("Refreshing token");
 
     Some(Promise::spawn_local(async move {
-        let response = http::Request::get(&url)
+        let response = authorize(http::Request::get(&url), &auth)
             .credentials(RequestCredentials::Include)
             .send()
             .await
-            .unwrap();
-        response
-            .json::<RefreshResponse>()
+            .map_err(|e| format!("Unable to reach server: {}", e))?;
+        match response.status() {
+            401 | 403 => Ok(response
+                .json::<RefreshResponse>()
+                .await
+                .unwrap_or(RefreshResponse {
+                    status: "fail".to_string(),
+                    message: "Not authorized".to_string(),
+                    expires_in: None,
+                    access_token: None,
+                })),
+            _ => response
+                .json::<RefreshResponse>()
+                .await
+                .map_err(|e| e.to_string()),
+        }
+    }))
+}
+
+/// Asks the backend at `url`, i.e. `AppState::api_url("auth/logout")`, to clear the auth cookie.
+/// Resolves to an error if the server couldn't be reached or refused.
+pub fn logout(url: &str, auth: Auth) -> RefreshPromise {
+    let url = url.to_string();
+    log::debug!("Logging out");
+
+    Some(Promise::spawn_local(async move {
+        let response = authorize(http::Request::post(&url), &auth)
+            .send()
             .await
-            .map_err(|e| e.to_string())
+            .map_err(|e| format!("Unable to reach server: {}", e))?;
+        match response.status() {
+            200 => Ok(response
+                .json::<RefreshResponse>()
+                .await
+                .unwrap_or(RefreshResponse {
+                    status: "success".to_string(),
+                    message: "Logged out".to_string(),
+                    expires_in: None,
+                    access_token: None,
+                })),
+            status => Err(format!("Logout failed ({})", status)),
+        }
     }))
 }
 
@@ -41,15 +91,23 @@ pub fn check_refresh_promise(promise: &mut RefreshPromise) -> RefreshStatus {
     if let Some(p) = promise {
         res = RefreshStatus::InProgress;
         if let Some(result) = p.ready() {
-            if let Ok(result) = result {
-                if "success" == result.status {
+            res = match result {
+                Ok(result) if "success" == result.status => {
                     log::info!("Token refreshed");
-                    res = RefreshStatus::Success;
-                } else {
+                    RefreshStatus::Success {
+                        lifetime: result.expires_in.map(chrono::Duration::seconds),
+                        token: result.access_token.clone(),
+                    }
+                }
+                Ok(result) => {
                     log::error!("Failed to refresh token: {:?}", result);
-                    res = RefreshStatus::Failed(result.message.clone());
+                    RefreshStatus::Failed(result.message.clone())
+                }
+                Err(e) => {
+                    log::error!("Failed to refresh token: {}", e);
+                    RefreshStatus::NetworkError(e.clone())
                 }
-            }
+            };
             *promise = None;
         }
     }
@@ -0,0 +1,121 @@
+use super::{
+    fetchers::{self, authorize, RetryPolicy},
+    Auth,
+};
+use gloo_net::http;
+use scoreboard_db::Score;
+use std::fmt::{self, Display, Formatter};
+
+/// Why [`fetch_scores`] came back without any scores.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FetchError {
+    /// The server couldn't be reached in time, or the response couldn't be read
+    Network(String),
+    /// Not logged in, or the session has expired
+    Unauthorized,
+    /// The server wants to be left alone for this many seconds
+    RateLimited(u32),
+    /// Any other response than 200, with its status text
+    Status(u16, String),
+    /// The response wasn't a list of scores
+    InvalidData(String),
+}
+
+impl Display for FetchError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            FetchError::Network(e) => write!(f, "{}", e),
+            FetchError::Unauthorized => write!(f, "Not authorized"),
+            FetchError::RateLimited(seconds) => write!(f, "Rate limited, retry in {}s", seconds),
+            FetchError::Status(status, text) => {
+                write!(f, "{}", fetchers::error_message(*status, text))
+            }
+            FetchError::InvalidData(e) => write!(f, "Invalid scoreboard data: {}", e),
+        }
+    }
+}
+
+/// A score as the backend sends it. `Score` has no submission time,
+/// so that's kept next to it, for backends that send one.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+pub struct FetchedScore {
+    #[serde(flatten)]
+    pub score: Score,
+    #[serde(default)]
+    pub submitted_at: Option<String>,
+}
+
+/// What a reply from the scores endpoint comes to. An empty 200 means no scores yet.
+fn read_response(
+    status: u16,
+    status_text: &str,
+    retry_after: Option<&str>,
+    text: &str,
+) -> Result<Vec<FetchedScore>, FetchError> {
+    match status {
+        200 if text.trim().is_empty() => Ok(Vec::new()),
+        200 => serde_json::from_str(text).map_err(|e| FetchError::InvalidData(e.to_string())),
+        401 => Err(FetchError::Unauthorized),
+        429 => Err(FetchError::RateLimited(fetchers::retry_after_seconds(
+            retry_after,
+            chrono::Utc::now(),
+        ))),
+        status => Err(FetchError::Status(status, status_text.to_string())),
+    }
+}
+
+/// Fetches the scores at `url`, e.g. `AppState::api_url("game/scores/<table>")`.
+/// Needs no egui, so it works just as well for tests or health checks. Network errors
+/// are retried as `retry` allows, but an expired login is left to the caller.
+pub async fn fetch_scores(
+    url: &str,
+    auth: &Auth,
+    retry: RetryPolicy,
+) -> Result<Vec<FetchedScore>, FetchError> {
+    let timeout_seconds = fetchers::DEFAULT_TIMEOUT_SECONDS;
+    let response = fetchers::send_with_retries(retry, timeout_seconds, || {
+        authorize(http::Request::get(url), auth)
+            .build()
+            .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(FetchError::Network)?;
+    let text = fetchers::read_text(&response, timeout_seconds)
+        .await
+        .map_err(FetchError::Network)?;
+    let retry_after = response.headers().get("Retry-After");
+    read_response(
+        response.status(),
+        &response.status_text(),
+        retry_after.as_deref(),
+        &text,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_response() {
+        let body = r#"[{"name":"alice","command":"./run","time_ns":12.5,"language":"Rust","submitted_at":"2023-11-01T10:00:00Z"}]"#;
+        let scores = read_response(200, "OK", None, body).unwrap();
+        assert_eq!(scores[0].score.name, "alice");
+        assert_eq!(
+            scores[0].submitted_at.as_deref(),
+            Some("2023-11-01T10:00:00Z")
+        );
+        assert_eq!(read_response(200, "OK", None, ""), Ok(Vec::new()));
+
+        assert_eq!(
+            read_response(401, "Unauthorized", None, "{}"),
+            Err(FetchError::Unauthorized)
+        );
+        assert_eq!(
+            read_response(429, "Too Many Requests", Some("5"), ""),
+            Err(FetchError::RateLimited(5))
+        );
+        let error = read_response(503, "Service Unavailable", None, "down").unwrap_err();
+        assert_eq!(error.to_string(), "Error 503: Service Unavailable");
+    }
+}
@@ -1,10 +1,66 @@
 use super::{
-    fetchers::{RequestStatus, Requestor},
+    fetchers::{RequestStatus, Requestor, AUTH_ERROR},
     AppState, Languages,
 };
+use flate2::{write::GzEncoder, Compression};
+use std::collections::HashMap;
 use std::fmt::Display;
+use std::io::Write;
 use std::sync::{Arc, Mutex};
-use web_sys::FormData;
+use web_sys::{Blob, FormData};
+
+/// Largest binary the frontend will attempt to upload.
+pub const MAX_BINARY_SIZE: usize = 5 * 1024 * 1024;
+
+/// Code bigger than this, extra files included, is gzipped before it's sent,
+/// smaller code goes as plain JSON.
+pub const COMPRESSION_THRESHOLD: usize = 32 * 1024;
+
+fn gzip(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(bytes).map_err(|e| e.to_string())?;
+    encoder.finish().map_err(|e| e.to_string())
+}
+
+fn to_blob(bytes: &[u8]) -> Blob {
+    let uint8arr = js_sys::Uint8Array::new(&unsafe { js_sys::Uint8Array::view(bytes) }.into());
+    let array = js_sys::Array::new();
+    array.push(&uint8arr.buffer());
+    Blob::new_with_u8_array_sequence(array.as_ref()).unwrap()
+}
+
+fn last_languages_id() -> egui::Id {
+    egui::Id::new("last_language_per_challenge")
+}
+
+/// The language `challenge` was last submitted in, or the default if it never was.
+/// Kept in egui's persisted memory, so it's still there after a restart.
+pub fn last_language(ctx: &egui::Context, challenge: &str) -> Languages {
+    ctx.data_mut(|d| d.get_persisted::<HashMap<String, Languages>>(last_languages_id()))
+        .and_then(|languages| languages.get(challenge).copied())
+        .unwrap_or_default()
+}
+
+/// Remembers the language `submission` went out in, for [`last_language`].
+pub fn remember_language(ctx: &egui::Context, submission: &Submission) {
+    let Some(challenge) = &submission.challenge else {
+        return;
+    };
+    ctx.data_mut(|d| {
+        d.get_persisted_mut_or_default::<HashMap<String, Languages>>(last_languages_id())
+            .insert(challenge.clone(), submission.language);
+    });
+}
+
+fn nice_size(bytes: usize) -> String {
+    if bytes < 1024 {
+        format!("{} B", bytes)
+    } else if bytes < 1024 * 1024 {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    } else {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    }
+}
 
 #[derive(Default, Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct Submission {
@@ -16,32 +72,57 @@ pub struct Submission {
     pub code: Option<String>,
     #[serde(skip)]
     pub binary: Option<Vec<u8>>,
+    /// More source files, for challenges that need them.
+    /// `filename` and `code` are still the main one.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<SourceFile>,
 }
 
+/// A source file sent along with the main one.
+/// Named like the form fields, so JSON and form posts carry the same shape.
+#[derive(Default, Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SourceFile {
+    pub filename: String,
+    pub code: String,
+}
+
+/// Form fields as (name, value)
+type Fields<T> = Vec<(String, T)>;
+
 impl Submission {
-    pub fn to_formdata(&self) -> Option<FormData> {
-        let challenge = match &self.challenge {
-            Some(challenge) => challenge,
-            None => return None,
-        };
+    /// Everything but the binary, in the order it goes into the form.
+    /// Extra files are numbered, `files[0].filename` and `files[0].code` and so on.
+    fn form_fields(&self) -> Option<Vec<(String, String)>> {
+        let challenge = self.challenge.as_ref()?;
+        let mut fields = vec![
+            ("challenge".to_string(), challenge.clone()),
+            ("filename".to_string(), self.filename.clone()),
+            ("language".to_string(), self.language.to_string()),
+            ("test".to_string(), self.test.to_string()),
+        ];
+        if let Some(code) = &self.code {
+            fields.push(("code".to_string(), code.clone()));
+        }
+        for (i, file) in self.files.iter().enumerate() {
+            fields.push((format!("files[{}].filename", i), file.filename.clone()));
+            fields.push((format!("files[{}].code", i), file.code.clone()));
+        }
+        Some(fields)
+    }
+
+    /// All the code that goes out, the main file and the extra ones.
+    fn code_size(&self) -> usize {
+        let extra: usize = self.files.iter().map(|f| f.code.len()).sum();
+        self.code.as_ref().map_or(0, String::len) + extra
+    }
 
+    pub fn to_formdata(&self) -> Option<FormData> {
         let form = FormData::new().unwrap();
-        form.append_with_str("challenge", challenge).unwrap();
-        form.append_with_str("filename", &self.filename).unwrap();
-        form.append_with_str("language", &self.language.to_string())
-            .unwrap();
-        form.append_with_str("test", &self.test.to_string())
-            .unwrap();
-        if let Some(code) = &self.code {
-            form.append_with_str("code", code).unwrap();
+        for (name, value) in self.form_fields()? {
+            form.append_with_str(&name, &value).unwrap();
         }
         if let Some(binary) = &self.binary {
-            let uint8arr =
-                js_sys::Uint8Array::new(&unsafe { js_sys::Uint8Array::view(binary) }.into());
-            let array = js_sys::Array::new();
-            array.push(&uint8arr.buffer());
-            let blob = web_sys::Blob::new_with_u8_array_sequence(array.as_ref()).unwrap();
-            form.append_with_blob("binary", &blob).unwrap();
+            form.append_with_blob("binary", &to_blob(binary)).unwrap();
         }
 
         log::info!("Form: {:?}", form);
@@ -49,24 +130,78 @@ impl Submission {
         Some(form)
     }
 
-    pub fn check_sender(sender: &mut Option<Requestor>) -> SubmissionResult {
+    /// [`Self::form_fields`] split into those sent as text and the code, gzipped.
+    /// That's `code` and every `files[i].code`.
+    fn compressed_fields(&self) -> Option<(Fields<String>, Fields<Vec<u8>>)> {
+        let (code, text): (Vec<_>, Vec<_>) = self
+            .form_fields()?
+            .into_iter()
+            .partition(|(name, _)| name == "code" || name.ends_with(".code"));
+        let code = code
+            .into_iter()
+            .map(|(name, value)| Ok((name, gzip(value.as_bytes())?)))
+            .collect::<Result<Vec<_>, String>>()
+            .map_err(|e| log::error!("Failed to compress code: {}", e))
+            .ok()?;
+        Some((text, code))
+    }
+
+    /// Like [`Self::to_formdata`], but all of the code goes as gzipped blobs,
+    /// with `content-encoding` telling the backend to unpack them.
+    pub fn to_formdata_compressed(&self) -> Option<FormData> {
+        self.code.as_ref()?;
+        let (text, code) = self.compressed_fields()?;
+        let form = FormData::new().unwrap();
+        for (name, value) in text {
+            form.append_with_str(&name, &value).unwrap();
+        }
+        for (name, value) in &code {
+            form.append_with_blob(name, &to_blob(value)).unwrap();
+        }
+        form.append_with_str("content-encoding", "gzip").unwrap();
+        log::debug!(
+            "Compressed code from {} to {}",
+            nice_size(self.code_size()),
+            nice_size(code.iter().map(|(_, value)| value.len()).sum())
+        );
+        Some(form)
+    }
+
+    /// `test` is whether `sender` has a test run out, only scored runs count as accepted.
+    pub fn check_sender(sender: &mut Option<Requestor>, test: bool) -> SubmissionResult {
         if let Some(requestor) = sender {
             let result = &requestor.check_promise();
 
             match result {
                 RequestStatus::Success(text) => {
-                    *sender = None;
-                    match serde_json::from_str::<SubmissionResult>(text) {
-                        Ok(submission_response) => submission_response.clone(),
-                        Err(error) => SubmissionResult::Failure {
-                            message: error.to_string(),
-                        },
+                    let Some(result) = read_reply(text, || requestor.retry_auth()) else {
+                        log::debug!("Submission not authorized, refreshing login");
+                        return SubmissionResult::Busy;
+                    };
+                    {
+                        let mut app_state = requestor.app_state.lock().unwrap();
+                        match result {
+                            SubmissionResult::Success { .. } if !test => {
+                                app_state.submissions_accepted += 1
+                            }
+                            SubmissionResult::NotAuthorized => app_state.session_expired = true,
+                            _ => {}
+                        }
                     }
+                    *sender = None;
+                    result
+                }
+                // The refresh didn't work either, so it's up to the user to log in
+                RequestStatus::Failed(e) if e == AUTH_ERROR => {
+                    *sender = None;
+                    SubmissionResult::NotAuthorized
                 }
                 RequestStatus::Failed(e) => {
                     *sender = None;
                     SubmissionResult::Failure {
+                        reason: FailureReason::Internal,
                         message: e.to_string(),
+                        output: None,
                     }
                 }
                 RequestStatus::InProgress => SubmissionResult::Busy,
@@ -78,12 +213,19 @@ impl Submission {
     }
 
     pub fn sender(&self, app_state: Arc<Mutex<AppState>>, url: &str) -> Option<Requestor> {
-        let mut submitter = if self.code.is_some() {
-            let submission = Some(serde_json::to_string(&self).unwrap());
-            Requestor::new_post(app_state, url, true, submission)
-        } else {
-            let submission = self.to_formdata();
-            Requestor::new_form_post(app_state, url, true, submission)
+        let mut submitter = match &self.code {
+            Some(_) if self.code_size() > COMPRESSION_THRESHOLD => {
+                let submission = self.to_formdata_compressed();
+                Requestor::new_form_post(app_state, url, true, submission)
+            }
+            Some(_) => {
+                let submission = Some(serde_json::to_string(&self).unwrap());
+                Requestor::new_post(app_state, url, true, submission)
+            }
+            None => {
+                let submission = self.to_formdata();
+                Requestor::new_form_post(app_state, url, true, submission)
+            }
         };
         submitter.send();
         Some(submitter)
@@ -99,16 +241,148 @@ impl Submission {
         if self.code.is_none() && self.binary.is_none() {
             return Err("Code not specified".to_string());
         }
+        if let Some(binary) = &self.binary {
+            if binary.len() > MAX_BINARY_SIZE {
+                return Err(format!(
+                    "Binary is {}, the limit is {}",
+                    nice_size(binary.len()),
+                    nice_size(MAX_BINARY_SIZE)
+                ));
+            }
+        }
 
         let rx = regex::Regex::new(r"^[a-zA-Z0-9_\-\.]+$").unwrap();
         if !rx.is_match(&self.filename) {
             return Err("Filename contains invalid characters".to_string());
         }
+        validate_extension(&self.filename, self.language)?;
 
+        let mut seen = vec![self.filename.as_str()];
+        for SourceFile { filename, .. } in &self.files {
+            if !rx.is_match(filename) {
+                return Err(format!("{} contains invalid characters", filename));
+            }
+            if seen.contains(&filename.as_str()) {
+                return Err(format!("{} is included more than once", filename));
+            }
+            validate_extension(filename, self.language)?;
+            seen.push(filename);
+        }
         Ok(())
     }
 }
 
+/// Filenames without a recognised source extension are left alone,
+/// otherwise the extension has to belong to the selected language.
+fn validate_extension(filename: &str, language: Languages) -> Result<(), String> {
+    let extension = match filename.rsplit_once('.') {
+        Some((_, extension)) => extension.to_lowercase(),
+        None => return Ok(()),
+    };
+
+    match Languages::from_extension(&extension) {
+        Some(l) if l != language => Err(format!(
+            ".{} files can't be submitted as {}",
+            extension, language
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Why a submission failed, so the UI can say what to do about it.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum FailureReason {
+    CompileError,
+    RuntimeError,
+    WrongAnswer,
+    Timeout,
+    /// Caught here before anything was sent
+    Invalid,
+    /// Anything else, including reasons this frontend doesn't know yet
+    #[default]
+    #[serde(other)]
+    Internal,
+}
+
+impl FailureReason {
+    pub fn icon(&self) -> &'static str {
+        match self {
+            FailureReason::CompileError => "🔨",
+            FailureReason::RuntimeError => "💥",
+            FailureReason::WrongAnswer => "❌",
+            FailureReason::Timeout => "⏱",
+            FailureReason::Invalid => "✏",
+            FailureReason::Internal => "⚠",
+        }
+    }
+
+    pub fn guidance(&self) -> &'static str {
+        match self {
+            FailureReason::CompileError => {
+                "Check the build output and that the right language is selected"
+            }
+            FailureReason::RuntimeError => "Your program crashed, check it runs locally",
+            FailureReason::WrongAnswer => "Your program ran but its output was wrong",
+            FailureReason::Timeout => "Your program took too long, try a faster approach",
+            FailureReason::Invalid => "Fix the submission and try again",
+            FailureReason::Internal => "Something went wrong on our side, try again later",
+        }
+    }
+}
+
+impl Display for FailureReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            FailureReason::CompileError => "Compile error",
+            FailureReason::RuntimeError => "Runtime error",
+            FailureReason::WrongAnswer => "Wrong answer",
+            FailureReason::Timeout => "Timed out",
+            FailureReason::Invalid => "Invalid submission",
+            FailureReason::Internal => "Failure",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+/// What building and running a test submission printed, any part may be empty.
+#[derive(Default, Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct RunOutput {
+    pub compiler: String,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl RunOutput {
+    /// The parts with something in them, with a title for each.
+    pub fn sections(&self) -> Vec<(&'static str, &str)> {
+        [
+            ("Compiler", &self.compiler),
+            ("Output", &self.stdout),
+            ("Errors", &self.stderr),
+        ]
+        .into_iter()
+        .filter(|(_, text)| !text.trim().is_empty())
+        .map(|(title, text)| (title, text.as_str()))
+        .collect()
+    }
+}
+
+/// What the reply to a finished submission comes to.
+/// A lapsed login is handed to `retry_auth` first, `None` means it's being refreshed
+/// and the same submission goes out again once it is.
+fn read_reply(text: &str, retry_auth: impl FnOnce() -> bool) -> Option<SubmissionResult> {
+    match serde_json::from_str::<SubmissionResult>(text) {
+        Ok(SubmissionResult::NotAuthorized) if retry_auth() => None,
+        Ok(result) => Some(result),
+        Err(error) => Some(SubmissionResult::Failure {
+            reason: FailureReason::Internal,
+            message: error.to_string(),
+            output: None,
+        }),
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum SubmissionResult {
     #[default]
@@ -116,24 +390,394 @@ pub enum SubmissionResult {
     Success {
         score: u32,
         message: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        output: Option<RunOutput>,
     },
     Failure {
+        #[serde(default)]
+        reason: FailureReason,
         message: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        output: Option<RunOutput>,
     },
     NotAuthorized,
     Busy,
 }
 
+impl SubmissionResult {
+    /// Only sent back for test runs, and only by backends that capture it.
+    pub fn output(&self) -> Option<&RunOutput> {
+        match self {
+            SubmissionResult::Success { output, .. } | SubmissionResult::Failure { output, .. } => {
+                output.as_ref()
+            }
+            _ => None,
+        }
+    }
+}
+
 impl Display for SubmissionResult {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             SubmissionResult::NotStarted => write!(f, ""),
-            SubmissionResult::Success { score: _, message } => {
+            SubmissionResult::Success { message, .. } => {
                 write!(f, "{}", message)
             }
-            SubmissionResult::Failure { message } => write!(f, "Failure: {}", message),
-            SubmissionResult::NotAuthorized => write!(f, "Not authorized"),
+            SubmissionResult::Failure {
+                reason, message, ..
+            } => {
+                write!(f, "{}: {}", reason, message)
+            }
+            SubmissionResult::NotAuthorized => write!(f, "🔒 Not authorized, please log in"),
             SubmissionResult::Busy => write!(f, "Busy"),
         }
     }
 }
+
+/// One of the user's earlier submissions, as the backend reports it.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+pub struct PastSubmission {
+    #[serde(flatten)]
+    pub submission: Submission,
+    pub result: SubmissionResult,
+    #[serde(default)]
+    pub submitted_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl PastSubmission {
+    pub fn status(&self) -> &'static str {
+        match self.result {
+            SubmissionResult::Success { .. } => "Accepted",
+            SubmissionResult::Failure { .. } => "Failed",
+            SubmissionResult::NotAuthorized => "Not authorized",
+            SubmissionResult::Busy => "Busy",
+            SubmissionResult::NotStarted => "Pending",
+        }
+    }
+
+    pub fn score(&self) -> String {
+        match self.result {
+            SubmissionResult::Success { score, .. } => score.to_string(),
+            _ => "-".to_string(),
+        }
+    }
+}
+
+/// Newest first, anything without a time goes to the end.
+pub fn parse_submissions(text: &str) -> Result<Vec<PastSubmission>, String> {
+    let mut submissions: Vec<PastSubmission> =
+        serde_json::from_str(text).map_err(|e| format!("Invalid submissions: {}", e))?;
+    submissions.sort_by(|a, b| b.submitted_at.cmp(&a.submitted_at));
+    Ok(submissions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn submission(filename: &str, language: Languages) -> Submission {
+        Submission {
+            challenge: Some("2331".to_string()),
+            filename: filename.to_string(),
+            language,
+            code: Some("code".to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn source_file(filename: &str, code: &str) -> SourceFile {
+        SourceFile {
+            filename: filename.to_string(),
+            code: code.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_matching_extension() {
+        assert!(submission("main.rs", Languages::Rust).validate().is_ok());
+        assert!(submission("main.PY", Languages::Python).validate().is_ok());
+        assert!(submission("solver.cpp", Languages::Cpp).validate().is_ok());
+    }
+
+    #[test]
+    fn test_mismatched_extension() {
+        assert!(submission("main.py", Languages::Rust).validate().is_err());
+        assert!(submission("main.c", Languages::Cpp).validate().is_err());
+    }
+
+    #[test]
+    fn test_multi_file_form_fields() {
+        let mut run = submission("main.rs", Languages::Rust);
+        let single = run.form_fields().unwrap();
+        assert_eq!(single.len(), 5);
+        assert!(!single.iter().any(|(name, _)| name.starts_with("files")));
+
+        run.files = vec![
+            source_file("parser.rs", "mod parser;"),
+            source_file("solver.rs", "mod solver;"),
+        ];
+        let fields = run.form_fields().unwrap();
+        let extra: Vec<(&str, &str)> = fields[5..]
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+            .collect();
+        assert_eq!(
+            extra,
+            vec![
+                ("files[0].filename", "parser.rs"),
+                ("files[0].code", "mod parser;"),
+                ("files[1].filename", "solver.rs"),
+                ("files[1].code", "mod solver;"),
+            ]
+        );
+
+        run.challenge = None;
+        assert_eq!(run.form_fields(), None);
+    }
+
+    #[test]
+    fn test_multi_file_json() {
+        let mut run = submission("main.rs", Languages::Rust);
+        let json = serde_json::to_value(&run).unwrap();
+        assert!(json.get("files").is_none());
+
+        run.files = vec![source_file("parser.rs", "mod parser;")];
+        let json = serde_json::to_value(&run).unwrap();
+        assert_eq!(
+            json["files"],
+            serde_json::json!([{"filename": "parser.rs", "code": "mod parser;"}])
+        );
+    }
+
+    #[test]
+    fn test_compressed_fields() {
+        use std::io::Read;
+
+        let mut run = submission("main.rs", Languages::Rust);
+        run.files = vec![
+            source_file("parser.rs", "mod parser;"),
+            source_file("solver.rs", "mod solver;"),
+        ];
+        let (text, code) = run.compressed_fields().unwrap();
+        let text: Vec<&str> = text.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(
+            text,
+            vec![
+                "challenge",
+                "filename",
+                "language",
+                "test",
+                "files[0].filename",
+                "files[1].filename",
+            ]
+        );
+        let gzipped: Vec<&str> = code.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(gzipped, vec!["code", "files[0].code", "files[1].code"]);
+
+        let mut unpacked = String::new();
+        flate2::read::GzDecoder::new(code[2].1.as_slice())
+            .read_to_string(&mut unpacked)
+            .unwrap();
+        assert_eq!(unpacked, "mod solver;");
+    }
+
+    #[test]
+    fn test_code_size() {
+        let mut run = submission("main.rs", Languages::Rust);
+        assert_eq!(run.code_size(), 4);
+
+        let half = "x".repeat(COMPRESSION_THRESHOLD / 2 + 1);
+        run.files = vec![
+            source_file("parser.rs", &half),
+            source_file("solver.rs", &half),
+        ];
+        assert!(run.code_size() > COMPRESSION_THRESHOLD);
+
+        run.code = None;
+        assert_eq!(run.code_size(), 2 * half.len());
+    }
+
+    #[test]
+    fn test_multi_file_validation() {
+        let mut run = submission("main.rs", Languages::Rust);
+        run.files = vec![source_file("parser.rs", "")];
+        assert!(run.validate().is_ok());
+
+        run.files.push(source_file("main.rs", ""));
+        assert!(run.validate().is_err());
+
+        run.files = vec![source_file("parser.py", "")];
+        assert!(run.validate().is_err());
+
+        run.files = vec![source_file("my parser.rs", "")];
+        assert!(run.validate().is_err());
+    }
+
+    #[test]
+    fn test_last_language() {
+        let ctx = egui::Context::default();
+        assert_eq!(last_language(&ctx, "2331"), Languages::default());
+
+        let mut run = submission("main.rs", Languages::Rust);
+        remember_language(&ctx, &run);
+        run.challenge = Some("2332".to_string());
+        run.language = Languages::Go;
+        remember_language(&ctx, &run);
+        assert_eq!(last_language(&ctx, "2331"), Languages::Rust);
+        assert_eq!(last_language(&ctx, "2332"), Languages::Go);
+        assert_eq!(last_language(&ctx, "2333"), Languages::default());
+    }
+
+    #[test]
+    fn test_gzip_round_trip() {
+        use std::io::Read;
+
+        let code = "print(\"Hello world!\")\n".repeat(2000);
+        let compressed = gzip(code.as_bytes()).unwrap();
+        assert!(compressed.len() < code.len());
+
+        let mut unpacked = String::new();
+        flate2::read::GzDecoder::new(compressed.as_slice())
+            .read_to_string(&mut unpacked)
+            .unwrap();
+        assert_eq!(unpacked, code);
+    }
+
+    #[test]
+    fn test_binary_size_limit() {
+        let mut run = submission("my_binary", Languages::Rust);
+        run.code = None;
+
+        run.binary = Some(vec![0; MAX_BINARY_SIZE]);
+        assert!(run.validate().is_ok());
+
+        run.binary = Some(vec![0; MAX_BINARY_SIZE + 1]);
+        assert!(run.validate().is_err());
+
+        run.binary = Some(vec![0; 6 * 1024 * 1024]);
+        assert_eq!(
+            run.validate(),
+            Err("Binary is 6.0 MB, the limit is 5.0 MB".to_string())
+        );
+    }
+
+    #[test]
+    fn test_nice_size() {
+        assert_eq!(nice_size(512), "512 B");
+        assert_eq!(nice_size(1536), "1.5 KB");
+        assert_eq!(nice_size(3 * 1024 * 1024), "3.0 MB");
+    }
+
+    #[test]
+    fn test_unknown_extension() {
+        assert!(submission("my_solution", Languages::Go).validate().is_ok());
+        assert!(submission("v1.2", Languages::Go).validate().is_ok());
+    }
+
+    #[test]
+    fn test_parse_submissions() {
+        let body = r#"[
+            {"challenge":"2331","filename":"a.rs","language":"Rust","test":false,"code":"fn main() {}",
+             "result":{"Failure":{"message":"wrong"}},"submitted_at":"2023-11-01T10:00:00Z"},
+            {"challenge":"2331","filename":"b.py","language":"Python","test":false,"code":null,
+             "result":{"Success":{"score":42,"message":"ok"}},"submitted_at":"2023-11-02T10:00:00Z"}
+        ]"#;
+        let submissions = parse_submissions(body).unwrap();
+
+        assert_eq!(submissions.len(), 2);
+        assert_eq!(submissions[0].submission.language, Languages::Python);
+        assert_eq!(submissions[0].status(), "Accepted");
+        assert_eq!(submissions[0].score(), "42");
+        assert_eq!(submissions[1].status(), "Failed");
+        assert_eq!(submissions[1].score(), "-");
+        assert!(parse_submissions("{}").is_err());
+    }
+
+    #[test]
+    fn test_reply_resubmits_once() {
+        // As Requestor::retry_auth does, the login is only refreshed once per request
+        let mut retries = 1;
+        let mut retry_auth = || match retries {
+            0 => false,
+            _ => {
+                retries -= 1;
+                true
+            }
+        };
+        let not_authorized = r#""NotAuthorized""#;
+
+        assert_eq!(read_reply(not_authorized, &mut retry_auth), None);
+        assert_eq!(
+            read_reply(not_authorized, &mut retry_auth),
+            Some(SubmissionResult::NotAuthorized)
+        );
+        assert_eq!(
+            read_reply(r#"{"Success":{"score":42,"message":"ok"}}"#, || {
+                panic!("only a lapsed login is retried")
+            }),
+            Some(SubmissionResult::Success {
+                score: 42,
+                message: "ok".to_string(),
+                output: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_malformed_reply() {
+        let Some(SubmissionResult::Failure { reason, .. }) =
+            read_reply("<html>Bad Gateway</html>", || true)
+        else {
+            panic!("a body that can't be read should fail the submission");
+        };
+        assert_eq!(reason, FailureReason::Internal);
+    }
+
+    #[test]
+    fn test_failure_reason() {
+        let parse = |text| serde_json::from_str::<SubmissionResult>(text).unwrap();
+        let failure = |reason| SubmissionResult::Failure {
+            reason,
+            message: "oops".to_string(),
+            output: None,
+        };
+
+        assert_eq!(
+            parse(r#"{"Failure":{"reason":"CompileError","message":"oops"}}"#),
+            failure(FailureReason::CompileError)
+        );
+        assert_eq!(
+            parse(r#"{"Failure":{"reason":"OutOfMemory","message":"oops"}}"#),
+            failure(FailureReason::Internal)
+        );
+        assert_eq!(
+            parse(r#"{"Failure":{"message":"oops"}}"#),
+            failure(FailureReason::Internal)
+        );
+    }
+
+    #[test]
+    fn test_run_output() {
+        let parse = |text| serde_json::from_str::<SubmissionResult>(text).unwrap();
+        assert_eq!(
+            parse(r#"{"Success":{"score":1,"message":"ok"}}"#).output(),
+            None
+        );
+
+        let result = parse(
+            r#"{"Failure":{"reason":"CompileError","message":"oops",
+                "output":{"compiler":"error[E0425]","stdout":"  \n"}}}"#,
+        );
+        let output = result.output().unwrap();
+        assert_eq!(output.stderr, "");
+        assert_eq!(output.sections(), vec![("Compiler", "error[E0425]")]);
+        // Only written out when there is some
+        let json = serde_json::to_string(&SubmissionResult::Success {
+            score: 1,
+            message: "ok".to_string(),
+            output: None,
+        })
+        .unwrap();
+        assert!(!json.contains("output"));
+    }
+}
@@ -0,0 +1,25 @@
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+
+/// Whether the browser tab is in the background, so there is nobody to poll for.
+pub fn page_hidden() -> bool {
+    web_sys::window()
+        .and_then(|w| w.document())
+        .map_or(false, |d| d.hidden())
+}
+
+/// Nothing asks for frames while the tab is hidden, so without a nudge the
+/// timers waiting on the next frame would only catch up on the next mouse move.
+pub fn repaint_when_shown(ctx: &egui::Context) -> Result<(), String> {
+    let document = web_sys::window()
+        .and_then(|w| w.document())
+        .ok_or("No document available")?;
+    let ctx = ctx.clone();
+    let on_change = Closure::<dyn FnMut()>::new(move || ctx.request_repaint());
+    document
+        .add_event_listener_with_callback("visibilitychange", on_change.as_ref().unchecked_ref())
+        .map_err(|e| format!("{:?}", e))?;
+    // Lives as long as the page does
+    on_change.forget();
+    Ok(())
+}
@@ -1,10 +1,6 @@
 #![warn(clippy::all, rust_2018_idioms)]
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")] // hide console window on Windows in release
 
-mod apps;
-mod components;
-mod helpers;
-
 // When compiling natively:
 #[cfg(not(target_arch = "wasm32"))]
 fn main() -> eframe::Result<()> {
@@ -31,8 +27,12 @@ fn main() {
     let web_options = eframe::WebOptions::default();
     log::debug!("Starting eframe on wasm32");
 
-    wasm_bindgen_futures::spawn_local(async {
-        eframe::WebRunner::new()
+    // Has to come after the runner, which installs its own panic hook
+    let runner = eframe::WebRunner::new();
+    challenge_frontend::helpers::panic_overlay::install();
+
+    wasm_bindgen_futures::spawn_local(async move {
+        runner
             .start(
                 "the_canvas_id", // hardcode it
                 web_options,
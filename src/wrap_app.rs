@@ -1,11 +1,17 @@
 use crate::{
     apps::{self},
-    background_processes::{ChallengeFetcher, LoginFetcher},
+    background_processes::{ChallengeFetcher, Health, HealthCheck, LanguageFetcher, LoginFetcher},
     code_editor,
-    helpers::AppState,
+    components::notifications::{self, Level},
+    helpers::{
+        format::{ClockFormat, Formatting, NumberFormat},
+        normalize_backend_url, visibility, AppState, DEFAULT_BACKEND_URL,
+    },
 };
 #[cfg(target_arch = "wasm32")]
 use core::any::Any;
+use egui_notify::Toasts;
+use std::collections::BTreeSet;
 use std::sync::{Arc, Mutex};
 
 #[derive(serde::Deserialize, serde::Serialize, Default)]
@@ -24,6 +30,22 @@ impl eframe::App for CodeEditorApp {
 pub struct CodeChallengeApp {
     #[serde(skip)]
     pub windows: apps::app_windows::AppWindows,
+    /// The windows themselves aren't saved, so keep track of which ones were open.
+    /// `None` until the first frame, so a fresh start gets the default set.
+    open_windows: Option<BTreeSet<String>>,
+    /// This tab only shows one detached app, so it mustn't save anything over
+    /// what the main tab had open
+    #[serde(skip)]
+    detached: bool,
+}
+
+impl CodeChallengeApp {
+    fn restore_open_windows(&mut self) {
+        if let Some(open) = &self.open_windows {
+            self.windows.set_open_windows(open.clone());
+        }
+        self.detached = self.windows.open_detached();
+    }
 }
 
 impl eframe::App for CodeChallengeApp {
@@ -35,6 +57,9 @@ impl eframe::App for CodeChallengeApp {
     /// Called each time the UI needs repainting, which may be many times per second.
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         self.windows.ui(ctx);
+        if !self.detached {
+            self.open_windows = Some(self.windows.open_windows().clone());
+        }
 
         egui::CentralPanel::default().show(ctx, |ui| {
             ui.with_layout(egui::Layout::bottom_up(egui::Align::LEFT), |ui| {
@@ -71,11 +96,49 @@ impl From<Anchor> for egui::WidgetText {
     }
 }
 
+#[derive(Copy, Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize, Default)]
+enum ThemePreference {
+    #[default]
+    System,
+    Light,
+    Dark,
+}
+
+impl ThemePreference {
+    fn visuals(&self, frame: &eframe::Frame) -> egui::Visuals {
+        match self {
+            ThemePreference::System => frame
+                .info()
+                .system_theme
+                .unwrap_or(eframe::Theme::Dark)
+                .egui_visuals(),
+            ThemePreference::Light => egui::Visuals::light(),
+            ThemePreference::Dark => egui::Visuals::dark(),
+        }
+    }
+}
+
+impl std::fmt::Display for ThemePreference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ThemePreference::System => write!(f, "💻 Follow system"),
+            ThemePreference::Light => write!(f, "☀ Light"),
+            ThemePreference::Dark => write!(f, "🌙 Dark"),
+        }
+    }
+}
+
 #[derive(serde::Deserialize, serde::Serialize, Default)]
+#[serde(default)]
 pub struct State {
     landing: CodeChallengeApp,
     code_editor: CodeEditorApp,
     selected_anchor: Anchor,
+    theme: ThemePreference,
+    /// `None` uses the address the app was built with
+    backend_url: Option<String>,
+    default_challenge: Option<String>,
+    formatting: Formatting,
 }
 
 #[derive(serde::Deserialize, serde::Serialize)]
@@ -87,7 +150,22 @@ pub struct WrapApp {
     #[serde(skip)]
     challenge_fetcher: ChallengeFetcher,
     #[serde(skip)]
+    language_fetcher: LanguageFetcher,
+    #[serde(skip)]
     login_fetcher: LoginFetcher,
+    #[serde(skip)]
+    health_check: HealthCheck,
+    #[serde(skip)]
+    toasts: Toasts,
+    #[serde(skip)]
+    settings_open: bool,
+    #[serde(skip)]
+    backend_url_input: String,
+    /// A maintenance message the user closed, it stays hidden until the message changes
+    #[serde(skip)]
+    dismissed_maintenance: Option<String>,
+    #[serde(skip)]
+    backend_url_error: Option<String>,
 }
 
 impl Default for WrapApp {
@@ -97,7 +175,14 @@ impl Default for WrapApp {
             state: State::default(),
             app_state,
             challenge_fetcher: ChallengeFetcher::default(),
+            language_fetcher: LanguageFetcher::default(),
             login_fetcher: LoginFetcher::default(),
+            health_check: HealthCheck::default(),
+            toasts: Toasts::default(),
+            settings_open: false,
+            backend_url_input: String::new(),
+            backend_url_error: None,
+            dismissed_maintenance: None,
         }
     }
 }
@@ -111,22 +196,46 @@ impl WrapApp {
             state: State::default(),
             app_state: Arc::clone(&app_state),
             challenge_fetcher: ChallengeFetcher::new(app_state.clone()),
+            language_fetcher: LanguageFetcher::new(app_state.clone()),
             login_fetcher: LoginFetcher::new(app_state.clone()),
+            health_check: HealthCheck::new(app_state.clone()),
+            toasts: Toasts::default(),
+            settings_open: false,
+            backend_url_input: String::new(),
+            backend_url_error: None,
+            dismissed_maintenance: None,
             #[cfg(any(feature = "glow", feature = "wgpu"))]
             custom3d: crate::apps::Custom3d::new(cc),
         };
 
-        slf.state.code_editor.editor.app_state = Arc::clone(&app_state);
-        slf.state
-            .landing
-            .windows
-            .set_app_state_ref(Arc::clone(&app_state));
+        // Challenge instructions link images over http
+        egui_extras::install_image_loaders(&cc.egui_ctx);
 
+        if let Err(e) = visibility::repaint_when_shown(&cc.egui_ctx) {
+            log::warn!("Won't notice the tab coming back: {}", e);
+        }
+
+        // Restore before wiring up the shared state, or the restored apps would miss it
         if let Some(storage) = cc.storage {
             if let Some(state) = eframe::get_value(storage, eframe::APP_KEY) {
                 slf.state = state;
             }
         }
+        slf.state.landing.restore_open_windows();
+        if slf.state.landing.detached {
+            slf.state.selected_anchor = Anchor::Landing;
+        }
+        if let Some(url) = &slf.state.backend_url {
+            app_state.lock().unwrap().backend_url = url.clone();
+        }
+        app_state.lock().unwrap().default_challenge = slf.state.default_challenge.clone();
+        app_state.lock().unwrap().formatting = slf.state.formatting;
+
+        slf.state.code_editor.editor.app_state = Arc::clone(&app_state);
+        slf.state
+            .landing
+            .windows
+            .set_app_state_ref(Arc::clone(&app_state));
 
         slf
     }
@@ -151,6 +260,10 @@ impl WrapApp {
 impl eframe::App for WrapApp {
     #[cfg(feature = "persistence")]
     fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        // A detached tab shares storage with the main tab, whose settings it mustn't clobber
+        if self.state.landing.detached {
+            return;
+        }
         eframe::set_value(storage, eframe::APP_KEY, &self.state);
     }
 
@@ -168,7 +281,14 @@ impl eframe::App for WrapApp {
         }
 
         self.challenge_fetcher.tick();
-        self.login_fetcher.tick();
+        self.language_fetcher.tick();
+        self.login_fetcher.tick(ctx);
+        self.health_check.tick(ctx);
+
+        let visuals = self.state.theme.visuals(frame);
+        if ctx.style().visuals.dark_mode != visuals.dark_mode {
+            ctx.set_visuals(visuals);
+        }
 
         #[cfg(not(target_arch = "wasm32"))]
         if ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::F11)) {
@@ -181,8 +301,11 @@ impl eframe::App for WrapApp {
                 self.bar_contents(ui, frame);
             });
         });
+        self.maintenance_banner(ctx);
 
         self.show_selected_app(ctx, frame);
+        self.settings_ui(ctx);
+        notifications::show(ctx, &mut self.toasts);
 
         // On web, the browser controls `pixels_per_point`.
         if !frame.is_web() {
@@ -205,7 +328,29 @@ impl eframe::App for WrapApp {
 
 impl WrapApp {
     fn bar_contents(&mut self, ui: &mut egui::Ui, frame: &mut eframe::Frame) {
-        egui::widgets::global_dark_light_mode_switch(ui);
+        ui.menu_button("🎨 Theme", |ui| {
+            for theme in [
+                ThemePreference::System,
+                ThemePreference::Light,
+                ThemePreference::Dark,
+            ] {
+                if ui
+                    .radio_value(&mut self.state.theme, theme, theme.to_string())
+                    .clicked()
+                {
+                    ui.close_menu();
+                }
+            }
+        });
+
+        if ui
+            .toggle_value(&mut self.settings_open, "⚙ Settings")
+            .clicked()
+            && self.settings_open
+        {
+            self.backend_url_input = self.app_state.lock().unwrap().backend_url.clone();
+            self.backend_url_error = None;
+        }
 
         ui.separator();
 
@@ -225,10 +370,166 @@ impl WrapApp {
         self.state.selected_anchor = selected_anchor;
 
         ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            self.health_ui(ui);
             egui::warn_if_debug_build(ui);
         });
     }
 
+    /// A dot that's green, amber or red for a healthy, slow or unreachable backend.
+    fn health_ui(&self, ui: &mut egui::Ui) {
+        let (color, text) = match self.health_check.health() {
+            Health::Unknown => (
+                ui.visuals().weak_text_color(),
+                "Checking backend...".to_string(),
+            ),
+            Health::Healthy => (egui::Color32::GREEN, "Backend is healthy".to_string()),
+            Health::Slow(seconds) => (
+                egui::Color32::from_rgb(255, 191, 0),
+                format!("Backend is slow, took {:.1}s to answer", seconds),
+            ),
+            Health::Unreachable(e) => {
+                (egui::Color32::RED, format!("Backend is unreachable: {}", e))
+            }
+        };
+        let size = egui::Vec2::splat(ui.spacing().interact_size.y);
+        let (rect, response) = ui.allocate_exact_size(size, egui::Sense::hover());
+        ui.painter()
+            .circle_filled(rect.center(), size.y / 4.0, color);
+        response.on_hover_text(text);
+    }
+
+    fn maintenance_banner(&mut self, ctx: &egui::Context) {
+        let Some(message) = self.app_state.lock().unwrap().maintenance.clone() else {
+            return;
+        };
+        if self.dismissed_maintenance.as_ref() == Some(&message) {
+            return;
+        }
+        egui::TopBottomPanel::top("maintenance_banner").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.colored_label(ui.visuals().warn_fg_color, format!("🚧 {}", message));
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.small_button("✖").on_hover_text("Dismiss").clicked() {
+                        self.dismissed_maintenance = Some(message.clone());
+                    }
+                });
+            });
+        });
+    }
+
+    fn settings_ui(&mut self, ctx: &egui::Context) {
+        let mut open = self.settings_open;
+        egui::Window::new("⚙ Settings")
+            .open(&mut open)
+            .resizable(false)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.label("Backend URL:");
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut self.backend_url_input)
+                        .hint_text(DEFAULT_BACKEND_URL)
+                        .desired_width(250.0),
+                );
+                let entered =
+                    response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                ui.horizontal(|ui| {
+                    if ui.button("Apply").clicked() || entered {
+                        self.apply_backend_url(ui.ctx());
+                    }
+                    if ui.button("Use default").clicked() {
+                        self.backend_url_input = DEFAULT_BACKEND_URL.to_string();
+                        self.apply_backend_url(ui.ctx());
+                    }
+                });
+                if let Some(e) = &self.backend_url_error {
+                    ui.colored_label(ui.visuals().error_fg_color, e);
+                }
+                ui.separator();
+                self.default_challenge_ui(ui);
+                ui.separator();
+                self.formatting_ui(ui);
+            });
+        self.settings_open = open;
+    }
+
+    fn default_challenge_ui(&mut self, ui: &mut egui::Ui) {
+        let before = self.state.default_challenge.clone();
+        let challenges: Vec<String> = {
+            let app_state = self.app_state.lock().unwrap();
+            app_state
+                .challenges
+                .items
+                .iter()
+                .map(|c| c.command.clone())
+                .collect()
+        };
+        egui::ComboBox::from_label("Default challenge")
+            .selected_text(before.clone().unwrap_or("None".to_string()))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut self.state.default_challenge, None, "None");
+                for challenge in challenges {
+                    ui.selectable_value(
+                        &mut self.state.default_challenge,
+                        Some(challenge.clone()),
+                        challenge,
+                    );
+                }
+            })
+            .response
+            .on_hover_text("What the score board and challenge info show when they first open");
+        if self.state.default_challenge != before {
+            self.app_state.lock().unwrap().default_challenge = self.state.default_challenge.clone();
+        }
+    }
+
+    fn formatting_ui(&mut self, ui: &mut egui::Ui) {
+        let before = self.state.formatting;
+        let formatting = &mut self.state.formatting;
+        egui::ComboBox::from_label("Numbers")
+            .selected_text(formatting.numbers.to_string())
+            .show_ui(ui, |ui| {
+                for option in NumberFormat::iter() {
+                    ui.selectable_value(&mut formatting.numbers, option, option.to_string());
+                }
+            });
+        egui::ComboBox::from_label("Clock")
+            .selected_text(formatting.clock.to_string())
+            .show_ui(ui, |ui| {
+                for option in ClockFormat::iter() {
+                    ui.selectable_value(&mut formatting.clock, option, option.to_string());
+                }
+            });
+        if self.state.formatting != before {
+            self.app_state.lock().unwrap().formatting = self.state.formatting;
+        }
+    }
+
+    /* Point every app at a different backend. A session with
+    the old one means nothing to the new one, so that ends too */
+    fn apply_backend_url(&mut self, ctx: &egui::Context) {
+        let url = match normalize_backend_url(&self.backend_url_input) {
+            Ok(url) => url,
+            Err(e) => {
+                self.backend_url_error = Some(e);
+                return;
+            }
+        };
+        self.backend_url_error = None;
+        self.backend_url_input = url.clone();
+
+        let changed = {
+            let mut app_state = self.app_state.lock().unwrap();
+            let changed = app_state.backend_url != url;
+            app_state.backend_url = url.clone();
+            changed
+        };
+        if changed {
+            AppState::set_logged_out(&self.app_state);
+            notifications::notify(ctx, Level::Success, format!("Using backend {}", url));
+        }
+        self.state.backend_url = (url != DEFAULT_BACKEND_URL).then_some(url);
+    }
+
     fn show_selected_app(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
         let selected_anchor = self.state.selected_anchor;
         for (_name, anchor, app) in self.apps_iter_mut() {